@@ -1,4 +1,4 @@
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 
 #[derive(Debug, Error)]
 pub enum CRHError {
@@ -17,6 +17,6 @@ impl From<Error> for CRHError {
 
 impl From<CRHError> for Error {
     fn from(error: CRHError) -> Error {
-        Error::new(ErrorKind::Other, error.to_string())
+        Error::other(error.to_string())
     }
 }
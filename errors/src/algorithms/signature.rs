@@ -1,4 +1,4 @@
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 
 #[derive(Debug, Error)]
 pub enum SignatureError {
@@ -17,6 +17,6 @@ impl From<Error> for SignatureError {
 
 impl From<SignatureError> for Error {
     fn from(error: SignatureError) -> Error {
-        Error::new(ErrorKind::Other, error.to_string())
+        Error::other(error.to_string())
     }
 }
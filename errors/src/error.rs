@@ -0,0 +1,142 @@
+use crate::{
+    algorithms::{CRHError, CommitmentError, MerkleError, PRFError, SNARKError, SignatureError},
+    curves::ConstraintFieldError,
+    gadgets::SynthesisError,
+};
+
+/// The root of all snarkVM errors. Each variant displays as its inner error,
+/// and forwards `source` to it, so that no cause is lost by wrapping.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    CommitmentError(CommitmentError),
+
+    #[error(transparent)]
+    ConstraintFieldError(ConstraintFieldError),
+
+    #[error(transparent)]
+    CRHError(CRHError),
+
+    #[error(transparent)]
+    MerkleError(MerkleError),
+
+    #[error(transparent)]
+    PRFError(PRFError),
+
+    #[error(transparent)]
+    SignatureError(SignatureError),
+
+    #[error(transparent)]
+    SNARKError(SNARKError),
+
+    #[error(transparent)]
+    SynthesisError(SynthesisError),
+}
+
+impl Error {
+    /// Returns the numeric code of this error, for reporting through external APIs.
+    /// Codes are stable: a code is never changed or reused for a different variant.
+    pub fn code(&self) -> u32 {
+        match self {
+            Error::CommitmentError(_) => 100,
+            Error::CRHError(_) => 101,
+            Error::MerkleError(_) => 102,
+            Error::PRFError(_) => 103,
+            Error::SignatureError(_) => 104,
+            Error::SNARKError(_) => 105,
+            Error::ConstraintFieldError(_) => 200,
+            Error::SynthesisError(_) => 300,
+        }
+    }
+}
+
+impl From<CommitmentError> for Error {
+    fn from(error: CommitmentError) -> Self {
+        Error::CommitmentError(error)
+    }
+}
+
+impl From<ConstraintFieldError> for Error {
+    fn from(error: ConstraintFieldError) -> Self {
+        Error::ConstraintFieldError(error)
+    }
+}
+
+impl From<CRHError> for Error {
+    fn from(error: CRHError) -> Self {
+        Error::CRHError(error)
+    }
+}
+
+impl From<MerkleError> for Error {
+    fn from(error: MerkleError) -> Self {
+        Error::MerkleError(error)
+    }
+}
+
+impl From<PRFError> for Error {
+    fn from(error: PRFError) -> Self {
+        Error::PRFError(error)
+    }
+}
+
+impl From<SignatureError> for Error {
+    fn from(error: SignatureError) -> Self {
+        Error::SignatureError(error)
+    }
+}
+
+impl From<SNARKError> for Error {
+    fn from(error: SNARKError) -> Self {
+        Error::SNARKError(error)
+    }
+}
+
+impl From<SynthesisError> for Error {
+    fn from(error: SynthesisError) -> Self {
+        Error::SynthesisError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_codes() {
+        let errors: Vec<(Error, u32)> = vec![
+            (CommitmentError::Message("error".into()).into(), 100),
+            (CRHError::Message("error".into()).into(), 101),
+            (MerkleError::Message("error".into()).into(), 102),
+            (PRFError::Message("error".into()).into(), 103),
+            (SignatureError::Message("error".into()).into(), 104),
+            (SNARKError::Message("error".into()).into(), 105),
+            (ConstraintFieldError::Message("error".into()).into(), 200),
+            (SynthesisError::Unsatisfiable.into(), 300),
+        ];
+        for (error, code) in errors {
+            assert_eq!(error.code(), code, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_causes_are_kept() {
+        #[derive(Debug, Error)]
+        #[error("disk full")]
+        struct DiskFull;
+
+        #[derive(Debug, Error)]
+        #[error("write failed")]
+        struct WriteFailed(#[source] DiskFull);
+
+        let error = Error::from(SynthesisError::from(std::io::Error::other(WriteFailed(DiskFull))));
+        assert_eq!(error.code(), 300);
+        assert_eq!(error.to_string(), "I/O error: write failed");
+        assert_eq!(error.source().unwrap().to_string(), "disk full");
+
+        let error = Error::from(SNARKError::Message("bad proof".into()));
+        assert_eq!(error.to_string(), "bad proof");
+        assert!(error.source().is_none());
+    }
+}
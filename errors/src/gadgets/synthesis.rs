@@ -20,6 +20,72 @@ pub enum SynthesisError {
     MalformedVerifyingKey,
     /// During CRS generation, we observed an unconstrained auxiliary variable
     UnconstrainedVariable,
+    /// One of the errors above, with the location in the circuit where it occurred.
+    InContext {
+        context: SynthesisContext,
+        error: Box<SynthesisError>,
+    },
+}
+
+/// The location in a circuit where a `SynthesisError` occurred.
+/// Every field is optional, as not every failure has all three.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SynthesisContext {
+    /// The namespace path, such as `"verify/hash/round_3"`.
+    pub namespace: Option<String>,
+    /// The index of the constraint being enforced.
+    pub constraint: Option<usize>,
+    /// The identifier of the variable being allocated or evaluated.
+    pub variable: Option<String>,
+}
+
+impl SynthesisError {
+    /// Returns this error with the namespace path where it occurred.
+    pub fn with_namespace(self, namespace: impl Into<String>) -> Self {
+        self.with_context(|context| context.namespace = Some(namespace.into()))
+    }
+
+    /// Returns this error with the index of the constraint where it occurred.
+    pub fn with_constraint(self, constraint: usize) -> Self {
+        self.with_context(|context| context.constraint = Some(constraint))
+    }
+
+    /// Returns this error with the identifier of the variable where it occurred.
+    pub fn with_variable(self, variable: impl Into<String>) -> Self {
+        self.with_context(|context| context.variable = Some(variable.into()))
+    }
+
+    /// Returns the location where this error occurred, if known.
+    pub fn context(&self) -> Option<&SynthesisContext> {
+        match self {
+            SynthesisError::InContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns this error without its location.
+    pub fn kind(&self) -> &SynthesisError {
+        match self {
+            SynthesisError::InContext { error, .. } => error.kind(),
+            error => error,
+        }
+    }
+
+    fn with_context(self, update: impl FnOnce(&mut SynthesisContext)) -> Self {
+        let (mut context, error) = match self {
+            SynthesisError::InContext { context, error } => (context, error),
+            error => (SynthesisContext::default(), Box::new(error)),
+        };
+        update(&mut context);
+        SynthesisError::InContext { context, error }
+    }
+}
+
+impl SynthesisContext {
+    /// Returns whether no part of the location is known.
+    pub fn is_empty(&self) -> bool {
+        self.namespace.is_none() && self.constraint.is_none() && self.variable.is_none()
+    }
 }
 
 impl From<io::Error> for SynthesisError {
@@ -29,27 +95,98 @@ impl From<io::Error> for SynthesisError {
 }
 
 impl Error for SynthesisError {
-    fn description(&self) -> &str {
-        match *self {
-            SynthesisError::AssignmentMissing => "an assignment for a variable could not be computed",
-            SynthesisError::DivisionByZero => "division by zero",
-            SynthesisError::Unsatisfiable => "unsatisfiable constraint system",
-            SynthesisError::PolynomialDegreeTooLarge => "polynomial degree is too large",
-            SynthesisError::UnexpectedIdentity => "encountered an identity element in the CRS",
-            SynthesisError::IoError(_) => "encountered an I/O error",
-            SynthesisError::MalformedVerifyingKey => "malformed verifying key",
-            SynthesisError::UnconstrainedVariable => "auxiliary variable was unconstrained",
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            // The inner errors are already part of the message, so their sources are returned instead.
+            SynthesisError::IoError(e) => e.source(),
+            SynthesisError::InContext { error, .. } => error.source(),
+            _ => None,
         }
     }
 }
 
 impl fmt::Display for SynthesisError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        if let &SynthesisError::IoError(ref e) = self {
-            write!(f, "I/O error: ")?;
-            e.fmt(f)
-        } else {
-            write!(f, "{}", self.to_string())
+        match self {
+            SynthesisError::AssignmentMissing => write!(f, "an assignment for a variable could not be computed"),
+            SynthesisError::DivisionByZero => write!(f, "division by zero"),
+            SynthesisError::Unsatisfiable => write!(f, "unsatisfiable constraint system"),
+            SynthesisError::PolynomialDegreeTooLarge => write!(f, "polynomial degree is too large"),
+            SynthesisError::UnexpectedIdentity => write!(f, "encountered an identity element in the CRS"),
+            SynthesisError::IoError(e) => write!(f, "I/O error: {}", e),
+            SynthesisError::MalformedVerifyingKey => write!(f, "malformed verifying key"),
+            SynthesisError::UnconstrainedVariable => write!(f, "auxiliary variable was unconstrained"),
+            SynthesisError::InContext { context, error } if context.is_empty() => write!(f, "{}", error),
+            SynthesisError::InContext { context, error } => write!(f, "{} ({})", error, context),
         }
     }
 }
+
+impl fmt::Display for SynthesisContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut separator = "";
+        if let Some(namespace) = &self.namespace {
+            write!(f, "in namespace `{}`", namespace)?;
+            separator = ", ";
+        }
+        if let Some(constraint) = self.constraint {
+            write!(f, "{}at constraint {}", separator, constraint)?;
+            separator = ", ";
+        }
+        if let Some(variable) = &self.variable {
+            write!(f, "{}for variable `{}`", separator, variable)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            SynthesisError::Unsatisfiable.to_string(),
+            "unsatisfiable constraint system"
+        );
+
+        let error = SynthesisError::AssignmentMissing
+            .with_namespace("verify/hash")
+            .with_constraint(12)
+            .with_variable("aux_3");
+        assert_eq!(
+            error.to_string(),
+            "an assignment for a variable could not be computed \
+             (in namespace `verify/hash`, at constraint 12, for variable `aux_3`)"
+        );
+        assert_eq!(
+            SynthesisError::Unsatisfiable.with_constraint(7).to_string(),
+            "unsatisfiable constraint system (at constraint 7)"
+        );
+
+        let error = SynthesisError::InContext {
+            context: SynthesisContext::default(),
+            error: Box::new(SynthesisError::DivisionByZero),
+        };
+        assert_eq!(error.to_string(), "division by zero");
+    }
+
+    #[test]
+    fn test_context_and_source() {
+        let error = SynthesisError::Unsatisfiable.with_constraint(7).with_namespace("a/b");
+        assert!(matches!(error.kind(), SynthesisError::Unsatisfiable));
+        assert_eq!(error.context().unwrap().constraint, Some(7));
+        assert_eq!(error.context().unwrap().namespace.as_deref(), Some("a/b"));
+        assert!(error.source().is_none());
+
+        // Each message is reported once, either in the error itself or in its source.
+        let error = SynthesisError::from(io::Error::other("oops"));
+        assert_eq!(error.to_string(), "I/O error: oops");
+        assert!(error.source().is_none());
+
+        let error = error.with_constraint(3);
+        assert_eq!(error.to_string(), "I/O error: oops (at constraint 3)");
+        assert!(error.source().is_none());
+    }
+}
@@ -3,4 +3,8 @@ extern crate thiserror;
 
 pub mod algorithms;
 pub mod curves;
+
+pub mod error;
+pub use self::error::*;
+
 pub mod gadgets;
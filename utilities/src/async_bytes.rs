@@ -0,0 +1,270 @@
+//! Adapters that read and write the `ToBytes` and `FromBytes` formats over
+//! tokio's `AsyncRead` and `AsyncWrite`.
+//!
+//! Values are serialized synchronously, so each value is buffered on its own,
+//! but a stream of values never needs to be buffered as a whole.
+
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    error,
+    io::{ErrorKind, Result as IoResult},
+    length_encoding::{CompactSize, LengthEncoding},
+    Vec,
+};
+
+use core::future::Future;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// The maximum number of bytes read for a length prefix, as in an overlong LEB128 encoding.
+const MAX_LENGTH_SIZE: usize = 10;
+
+/// Reads a variable length integer from `reader`.
+#[inline]
+pub async fn read_variable_length_integer_async<R: AsyncRead + Unpin>(reader: &mut R) -> IoResult<usize> {
+    read_length_async::<CompactSize, _>(reader).await
+}
+
+/// Reads a length in the encoding `L` from `reader`. The bytes are read one at a
+/// time until they decode, so that no bytes after the length are consumed.
+pub async fn read_length_async<L: LengthEncoding, R: AsyncRead + Unpin>(reader: &mut R) -> IoResult<usize> {
+    let mut bytes = [0u8; MAX_LENGTH_SIZE];
+    for len in 1..=MAX_LENGTH_SIZE {
+        reader.read_exact(&mut bytes[len - 1..len]).await?;
+        match L::read_length(&bytes[..len]) {
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => continue,
+            result => return result,
+        }
+    }
+    Err(error("read_length_async: length prefix is too long"))
+}
+
+pub trait AsyncToBytes: ToBytes {
+    /// Serializes `self` into `writer`.
+    fn write_async<'a, W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &'a mut W,
+    ) -> impl Future<Output = IoResult<()>> + Send + 'a {
+        let bytes = self.to_bytes_vec();
+        async move { writer.write_all(&bytes?).await }
+    }
+
+    /// Serializes `self` into `writer`, preceded by its length in bytes as a variable length integer.
+    fn write_length_prefixed_async<'a, W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &'a mut W,
+    ) -> impl Future<Output = IoResult<()>> + Send + 'a {
+        self.write_length_prefixed_with_length_async::<CompactSize, W>(writer)
+    }
+
+    /// Serializes `self` into `writer`, preceded by its length in bytes in the encoding `L`.
+    fn write_length_prefixed_with_length_async<'a, L: LengthEncoding, W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &'a mut W,
+    ) -> impl Future<Output = IoResult<()>> + Send + 'a {
+        let bytes = self.to_bytes_vec();
+        async move {
+            let bytes = bytes?;
+            let mut prefix = Vec::with_capacity(L::length_size(bytes.len()));
+            L::write_length(bytes.len(), &mut prefix)?;
+            writer.write_all(&prefix).await?;
+            writer.write_all(&bytes).await
+        }
+    }
+}
+
+impl<T: ToBytes> AsyncToBytes for T {}
+
+pub trait AsyncFromBytes: FromBytes + Send {
+    /// Reads `Self` from exactly the next `num_bytes` bytes of `reader`.
+    fn read_async<R: AsyncRead + Unpin + Send>(
+        reader: &mut R,
+        num_bytes: usize,
+    ) -> impl Future<Output = IoResult<Self>> + Send + '_ {
+        async move {
+            let mut bytes = vec![0u8; num_bytes];
+            reader.read_exact(&mut bytes).await?;
+            Self::from_bytes_exact(&bytes)
+        }
+    }
+
+    /// Reads `Self` preceded by its length in bytes as a variable length integer,
+    /// rejecting lengths greater than `max_bytes` before allocating for them.
+    fn read_length_prefixed_async<R: AsyncRead + Unpin + Send>(
+        reader: &mut R,
+        max_bytes: usize,
+    ) -> impl Future<Output = IoResult<Self>> + Send + '_ {
+        Self::read_length_prefixed_with_length_async::<CompactSize, R>(reader, max_bytes)
+    }
+
+    /// Reads `Self` preceded by its length in bytes in the encoding `L`,
+    /// rejecting lengths greater than `max_bytes` before allocating for them.
+    fn read_length_prefixed_with_length_async<L: LengthEncoding, R: AsyncRead + Unpin + Send>(
+        reader: &mut R,
+        max_bytes: usize,
+    ) -> impl Future<Output = IoResult<Self>> + Send + '_ {
+        async move {
+            let num_bytes = read_length_async::<L, _>(reader).await?;
+            if num_bytes > max_bytes {
+                return Err(error("AsyncFromBytes: length prefix exceeds the maximum"));
+            }
+            Self::read_async(reader, num_bytes).await
+        }
+    }
+}
+
+impl<T: FromBytes + Send> AsyncFromBytes for T {}
+
+/// Writes the number of `items` as a variable length integer, followed by each item.
+#[inline]
+pub async fn write_vec_async<T: ToBytes, W: AsyncWrite + Unpin>(writer: &mut W, items: &[T]) -> IoResult<()> {
+    write_vec_with_length_async::<CompactSize, _, _>(writer, items).await
+}
+
+/// Writes the number of `items` in the length encoding `L`, followed by each item.
+pub async fn write_vec_with_length_async<L: LengthEncoding, T: ToBytes, W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    items: &[T],
+) -> IoResult<()> {
+    let mut buffer = Vec::new();
+    L::write_length(items.len(), &mut buffer)?;
+    writer.write_all(&buffer).await?;
+
+    for item in items {
+        buffer.clear();
+        item.write(&mut buffer)?;
+        writer.write_all(&buffer).await?;
+    }
+    Ok(())
+}
+
+/// Reads a vector written by `write_vec_async`, where every item is `item_size` bytes,
+/// rejecting counts greater than `max_items` before allocating for them.
+#[inline]
+pub async fn read_vec_async<T: FromBytes, R: AsyncRead + Unpin>(
+    reader: &mut R,
+    item_size: usize,
+    max_items: usize,
+) -> IoResult<Vec<T>> {
+    read_vec_with_length_async::<CompactSize, _, _>(reader, item_size, max_items).await
+}
+
+/// Reads a vector written by `write_vec_with_length_async` with the length encoding `L`,
+/// where every item is `item_size` bytes, rejecting counts greater than `max_items`.
+pub async fn read_vec_with_length_async<L: LengthEncoding, T: FromBytes, R: AsyncRead + Unpin>(
+    reader: &mut R,
+    item_size: usize,
+    max_items: usize,
+) -> IoResult<Vec<T>> {
+    let num_items = read_length_async::<L, _>(reader).await?;
+    if num_items > max_items {
+        return Err(error("read_vec_async: item count exceeds the maximum"));
+    }
+
+    let mut items = Vec::with_capacity(num_items);
+    let mut buffer = vec![0u8; item_size];
+    for _ in 0..num_items {
+        reader.read_exact(&mut buffer).await?;
+        items.push(T::from_bytes_exact(&buffer)?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        length_encoding::{FixedU32, Leb128},
+        variable_length_integer::variable_length_integer,
+        BTreeSet,
+    };
+
+    #[tokio::test]
+    async fn test_variable_length_integer_async() {
+        for value in [0u64, 252, 253, 65535, 65536, 1 << 40].iter() {
+            let bytes = variable_length_integer(*value);
+            let decoded = read_variable_length_integer_async(&mut &bytes[..]).await.unwrap();
+            assert_eq!(decoded as u64, *value);
+            assert!(
+                read_variable_length_integer_async(&mut &bytes[..bytes.len() - 1])
+                    .await
+                    .is_err()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_values_over_a_stream() {
+        let set: BTreeSet<u32> = (0..50u32).map(|i| i * i).collect();
+
+        let mut stream = Vec::new();
+        0xabcdu16.write_async(&mut stream).await.unwrap();
+        set.write_length_prefixed_async(&mut stream).await.unwrap();
+        assert_eq!(stream.len(), 2 + 1 + 1 + 50 * 4);
+
+        let mut reader = &stream[..];
+        assert_eq!(u16::read_async(&mut reader, 2).await.unwrap(), 0xabcd);
+        let decoded = BTreeSet::<u32>::read_length_prefixed_async(&mut reader, 1024)
+            .await
+            .unwrap();
+        assert_eq!(decoded, set);
+        assert!(reader.is_empty());
+
+        let mut reader = &stream[2..];
+        assert!(
+            BTreeSet::<u32>::read_length_prefixed_async(&mut reader, 200)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vec_over_a_stream() {
+        let items: Vec<[u64; 4]> = (0..300u64).map(|i| [i, i + 1, i + 2, i + 3]).collect();
+
+        let mut stream = Vec::new();
+        write_vec_async(&mut stream, &items).await.unwrap();
+        assert_eq!(
+            stream,
+            [variable_length_integer(300), items.to_bytes_vec().unwrap()].concat()
+        );
+
+        let decoded: Vec<[u64; 4]> = read_vec_async(&mut &stream[..], 32, 300).await.unwrap();
+        assert_eq!(decoded, items);
+        assert!(read_vec_async::<[u64; 4], _>(&mut &stream[..], 32, 299).await.is_err());
+        assert!(
+            read_vec_async::<[u64; 4], _>(&mut &stream[..stream.len() - 1], 32, 300)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_length_over_a_stream() {
+        let items: Vec<u16> = (0..200u16).collect();
+        let set: BTreeSet<u16> = items.iter().copied().collect();
+
+        let mut stream = Vec::new();
+        write_vec_with_length_async::<Leb128, _, _>(&mut stream, &items)
+            .await
+            .unwrap();
+        set.write_length_prefixed_with_length_async::<FixedU32, _>(&mut stream)
+            .await
+            .unwrap();
+        assert_eq!(&stream[..2], &[0xc8, 0x01]);
+
+        let mut reader = &stream[..];
+        let decoded: Vec<u16> = read_vec_with_length_async::<Leb128, _, _>(&mut reader, 2, 200)
+            .await
+            .unwrap();
+        assert_eq!(decoded, items);
+        assert_eq!(&reader[..4], &[145, 1, 0, 0]);
+        let decoded = BTreeSet::<u16>::read_length_prefixed_with_length_async::<FixedU32, _>(&mut reader, 401)
+            .await
+            .unwrap();
+        assert_eq!(decoded, set);
+        assert!(reader.is_empty());
+
+        assert!(read_length_async::<Leb128, _>(&mut &[0x80u8; 11][..]).await.is_err());
+        assert!(read_length_async::<Leb128, _>(&mut &[0x80u8][..]).await.is_err());
+    }
+}
@@ -1,15 +1,20 @@
 use crate::{
     bititerator::BitIterator,
-    bytes::{FromBytes, ToBytes},
+    bytes::{FromBytes, SerializedSize, ToBytes},
     io::{Read, Result as IoResult, Write},
-    rand::UniformRand,
+    rand::{UniformRand, UniformRandRange},
+    vec,
+    Vec,
 };
 
+use core::{
+    fmt::{Debug, Display},
+    ops::Range,
+};
 use rand::{
     distributions::{Distribution, Standard},
     Rng,
 };
-use std::fmt::{Debug, Display};
 
 bigint_impl!(BigInteger64, 1);
 bigint_impl!(BigInteger128, 2);
@@ -24,6 +29,7 @@ bigint_impl!(BigInteger832, 13);
 pub trait BigInteger:
     ToBytes
     + FromBytes
+    + SerializedSize
     + Copy
     + Clone
     + Debug
@@ -36,6 +42,7 @@ pub trait BigInteger:
     + Sync
     + 'static
     + UniformRand
+    + UniformRandRange
     + AsMut<[u64]>
     + AsRef<[u64]>
     + From<u64>
@@ -53,7 +53,7 @@ macro_rules! bigint_impl {
                 while n >= 64 {
                     let mut t = 0;
                     for i in &mut self.0 {
-                        ::std::mem::swap(&mut t, i);
+                        ::core::mem::swap(&mut t, i);
                     }
                     n -= 64;
                 }
@@ -90,7 +90,7 @@ macro_rules! bigint_impl {
                 while n >= 64 {
                     let mut t = 0;
                     for i in self.0.iter_mut().rev() {
-                        ::std::mem::swap(&mut t, i);
+                        ::core::mem::swap(&mut t, i);
                     }
                     n -= 64;
                 }
@@ -205,6 +205,13 @@ macro_rules! bigint_impl {
             }
         }
 
+        impl SerializedSize for $name {
+            #[inline]
+            fn serialized_size(&self) -> usize {
+                $num_limbs * 8
+            }
+        }
+
         impl FromBytes for $name {
             #[inline]
             fn read<R: Read>(reader: R) -> IoResult<Self> {
@@ -212,8 +219,10 @@ macro_rules! bigint_impl {
             }
         }
 
+        $crate::impl_canonical_via_bytes!($name);
+
         impl Display for $name {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                 for i in self.0.iter().rev() {
                     write!(f, "{:016X}", *i)?;
                 }
@@ -223,22 +232,22 @@ macro_rules! bigint_impl {
 
         impl Ord for $name {
             #[inline]
-            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
                 for (a, b) in self.0.iter().rev().zip(other.0.iter().rev()) {
                     if a < b {
-                        return ::std::cmp::Ordering::Less;
+                        return ::core::cmp::Ordering::Less;
                     } else if a > b {
-                        return ::std::cmp::Ordering::Greater;
+                        return ::core::cmp::Ordering::Greater;
                     }
                 }
 
-                ::std::cmp::Ordering::Equal
+                ::core::cmp::Ordering::Equal
             }
         }
 
         impl PartialOrd for $name {
             #[inline]
-            fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+            fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
                 Some(self.cmp(other))
             }
         }
@@ -249,6 +258,35 @@ macro_rules! bigint_impl {
             }
         }
 
+        impl UniformRandRange for $name {
+            fn rand_range<R: Rng + ?Sized>(range: Range<Self>, rng: &mut R) -> Self {
+                assert!(
+                    range.start < range.end,
+                    "UniformRandRange::rand_range called with an empty range"
+                );
+
+                let mut span = range.end;
+                span.sub_noborrow(&range.start);
+
+                // Sample values with no more bits than the span, so each attempt is accepted
+                // with probability greater than 1/2.
+                let top = span.0.iter().rposition(|limb| *limb != 0).unwrap();
+                let mask = u64::MAX >> span.0[top].leading_zeros();
+                loop {
+                    let mut value = $name::default();
+                    for limb in value.0[..top].iter_mut() {
+                        *limb = rng.gen();
+                    }
+                    value.0[top] = rng.gen::<u64>() & mask;
+
+                    if value < span {
+                        value.add_nocarry(&range.start);
+                        return value;
+                    }
+                }
+            }
+        }
+
         impl AsMut<[u64]> for $name {
             #[inline]
             fn as_mut(&mut self) -> &mut [u64] {
@@ -1,4 +1,4 @@
-use crate::{biginteger::*, rand::UniformRand};
+use crate::{biginteger::*, bytes::ToBytes, rand::UniformRand};
 
 use rand::SeedableRng;
 use rand_xorshift::XorShiftRng;
@@ -56,10 +56,27 @@ fn biginteger_bytes_test<B: BigInteger>() {
     let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
     let x: B = UniformRand::rand(&mut rng);
     x.write(bytes.as_mut()).unwrap();
+    assert_eq!(x.serialized_size(), crate::to_bytes![x].unwrap().len());
     let y = B::read(bytes.as_ref()).unwrap();
     assert_eq!(x, y);
 }
 
+fn biginteger_rand_range_test<B: BigInteger>() {
+    let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
+    let low = B::from(1000);
+    let high = B::from(1003);
+    for _ in 0..100 {
+        let x = B::rand_range(low..high, &mut rng);
+        assert!(low <= x && x < high);
+    }
+
+    let a: B = UniformRand::rand(&mut rng);
+    let b: B = UniformRand::rand(&mut rng);
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    let x = B::rand_range(low..high, &mut rng);
+    assert!(low <= x && x < high);
+}
+
 fn test_biginteger<B: BigInteger>(zero: B) {
     let mut rng = XorShiftRng::seed_from_u64(1231275789u64);
     let a: B = UniformRand::rand(&mut rng);
@@ -67,6 +84,7 @@ fn test_biginteger<B: BigInteger>(zero: B) {
     biginteger_arithmetic_test(a, b, zero);
     biginteger_bytes_test::<B>();
     biginteger_bits_test::<B>();
+    biginteger_rand_range_test::<B>();
 }
 
 #[test]
@@ -1,29 +1,227 @@
+use core::marker::PhantomData;
+
+/// A word type that a bit iterator can read bits from.
+pub trait BitWord: Copy {
+    const BITS: usize;
+
+    /// Returns the bit at position `i`, counting from the least significant bit.
+    fn bit(self, i: usize) -> bool;
+}
+
+macro_rules! bit_word {
+    ($($word:ty),*) => {
+        $(
+            impl BitWord for $word {
+                const BITS: usize = <$word>::BITS as usize;
+
+                #[inline]
+                fn bit(self, i: usize) -> bool {
+                    (self >> i) & 1 == 1
+                }
+            }
+        )*
+    };
+}
+
+bit_word!(u8, u16, u32, u64);
+
+/// Returns the number of bits in `words`, ignoring the most significant zero bits.
+fn significant_bits<W: BitWord>(words: &[W], end: usize) -> usize {
+    (0..end)
+        .rev()
+        .find(|i| words[i / W::BITS].bit(i % W::BITS))
+        .map_or(0, |i| i + 1)
+}
+
+/// Iterates over the bits of a little-endian slice of words, most significant bit first.
+/// Iterating backwards yields the bits least significant bit first.
 #[derive(Debug)]
-pub struct BitIterator<E> {
+pub struct BitIteratorBE<E, W = u64> {
     t: E,
-    n: usize,
+    /// The index of the next bit to yield from the back.
+    start: usize,
+    /// One past the index of the next bit to yield from the front.
+    end: usize,
+    _word: PhantomData<W>,
 }
 
-impl<E: AsRef<[u64]>> BitIterator<E> {
+/// The original name of `BitIteratorBE`.
+pub type BitIterator<E> = BitIteratorBE<E, u64>;
+
+impl<W: BitWord, E: AsRef<[W]>> BitIteratorBE<E, W> {
     pub fn new(t: E) -> Self {
-        let n = t.as_ref().len() * 64;
+        let end = t.as_ref().len() * W::BITS;
+
+        BitIteratorBE {
+            t,
+            start: 0,
+            end,
+            _word: PhantomData,
+        }
+    }
 
-        BitIterator { t, n }
+    /// Returns an iterator that skips the most significant zero bits,
+    /// so the first bit yielded (if any) is set.
+    pub fn without_leading_zeros(t: E) -> Self {
+        let mut iter = Self::new(t);
+        iter.end = significant_bits(iter.t.as_ref(), iter.end);
+        iter
+    }
+
+    #[inline]
+    fn bit(&self, i: usize) -> bool {
+        self.t.as_ref()[i / W::BITS].bit(i % W::BITS)
     }
 }
 
-impl<E: AsRef<[u64]>> Iterator for BitIterator<E> {
+impl<W: BitWord, E: AsRef<[W]>> Iterator for BitIteratorBE<E, W> {
     type Item = bool;
 
     fn next(&mut self) -> Option<bool> {
-        if self.n == 0 {
+        if self.start == self.end {
             None
         } else {
-            self.n -= 1;
-            let part = self.n / 64;
-            let bit = self.n - (64 * part);
+            self.end -= 1;
+            Some(self.bit(self.end))
+        }
+    }
 
-            Some(self.t.as_ref()[part] & (1 << bit) > 0)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<W: BitWord, E: AsRef<[W]>> DoubleEndedIterator for BitIteratorBE<E, W> {
+    fn next_back(&mut self) -> Option<bool> {
+        if self.start == self.end {
+            None
+        } else {
+            self.start += 1;
+            Some(self.bit(self.start - 1))
         }
     }
 }
+
+impl<W: BitWord, E: AsRef<[W]>> ExactSizeIterator for BitIteratorBE<E, W> {}
+
+/// Iterates over the bits of a little-endian slice of words, least significant bit first.
+/// Iterating backwards yields the bits most significant bit first.
+#[derive(Debug)]
+pub struct BitIteratorLE<E, W = u64> {
+    t: E,
+    /// The index of the next bit to yield from the front.
+    start: usize,
+    /// One past the index of the next bit to yield from the back.
+    end: usize,
+    _word: PhantomData<W>,
+}
+
+impl<W: BitWord, E: AsRef<[W]>> BitIteratorLE<E, W> {
+    pub fn new(t: E) -> Self {
+        let end = t.as_ref().len() * W::BITS;
+
+        BitIteratorLE {
+            t,
+            start: 0,
+            end,
+            _word: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that stops after the most significant set bit.
+    pub fn without_leading_zeros(t: E) -> Self {
+        let mut iter = Self::new(t);
+        iter.end = significant_bits(iter.t.as_ref(), iter.end);
+        iter
+    }
+
+    #[inline]
+    fn bit(&self, i: usize) -> bool {
+        self.t.as_ref()[i / W::BITS].bit(i % W::BITS)
+    }
+}
+
+impl<W: BitWord, E: AsRef<[W]>> Iterator for BitIteratorLE<E, W> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.start == self.end {
+            None
+        } else {
+            self.start += 1;
+            Some(self.bit(self.start - 1))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<W: BitWord, E: AsRef<[W]>> DoubleEndedIterator for BitIteratorLE<E, W> {
+    fn next_back(&mut self) -> Option<bool> {
+        if self.start == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(self.bit(self.end))
+        }
+    }
+}
+
+impl<W: BitWord, E: AsRef<[W]>> ExactSizeIterator for BitIteratorLE<E, W> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Vec;
+
+    #[test]
+    fn test_bit_iterator_be() {
+        let bits: Vec<bool> = BitIterator::new([0b1011u64]).collect();
+        assert_eq!(bits.len(), 64);
+        assert!(bits[..60].iter().all(|bit| !bit));
+        assert_eq!(&bits[60..], &[true, false, true, true]);
+
+        let bits: Vec<bool> = BitIteratorBE::without_leading_zeros([0b1011u64, 0]).collect();
+        assert_eq!(bits, [true, false, true, true]);
+        assert_eq!(BitIteratorBE::without_leading_zeros([0u64; 2]).len(), 0);
+    }
+
+    #[test]
+    fn test_bit_iterator_le() {
+        let bits: Vec<bool> = BitIteratorLE::without_leading_zeros([0b1011u64, 0]).collect();
+        assert_eq!(bits, [true, true, false, true]);
+
+        let limbs = [0x0123_4567_89ab_cdefu64, 0xfedc_ba98_7654_3210];
+        let le: Vec<bool> = BitIteratorLE::new(limbs).collect();
+        let mut be: Vec<bool> = BitIteratorBE::new(limbs).collect();
+        be.reverse();
+        assert_eq!(le, be);
+    }
+
+    #[test]
+    fn test_double_ended_and_exact_size() {
+        let limbs = [0x0123_4567_89ab_cdefu64, 0xfedc_ba98_7654_3210];
+        let mut iter = BitIteratorBE::new(limbs);
+        assert_eq!(iter.len(), 128);
+        assert_eq!(iter.next(), Some(true));
+        assert_eq!(iter.next_back(), Some(true));
+        assert_eq!(iter.len(), 126);
+
+        let forward: Vec<bool> = BitIteratorLE::new(limbs).collect();
+        let backward: Vec<bool> = BitIteratorBE::new(limbs).rev().collect();
+        assert_eq!(forward, backward);
+    }
+
+    #[test]
+    fn test_byte_slice_source() {
+        let limbs = [0x0123_4567_89ab_cdefu64];
+        let bytes = limbs[0].to_le_bytes();
+        assert!(BitIteratorLE::new(&bytes[..]).eq(BitIteratorLE::new(limbs)));
+        assert!(BitIteratorBE::new(bytes).eq(BitIteratorBE::new(limbs)));
+        assert!(BitIteratorBE::without_leading_zeros(bytes).eq(BitIteratorBE::without_leading_zeros(limbs)));
+    }
+}
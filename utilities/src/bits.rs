@@ -0,0 +1,200 @@
+use crate::{
+    io::{Read, Result as IoResult, Write},
+    Vec,
+};
+
+/// The order in which bits are packed into each byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The first bit is the most significant bit of the byte,
+    /// matching the big endian order of `BigInteger::to_bits`.
+    MsbFirst,
+    /// The first bit is the least significant bit of the byte.
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// Returns the mask of the `index`-th bit within a byte, where `index < 8`.
+    #[inline]
+    fn mask(self, index: u8) -> u8 {
+        match self {
+            BitOrder::MsbFirst => 0x80 >> index,
+            BitOrder::LsbFirst => 1 << index,
+        }
+    }
+}
+
+/// Packs bits into bytes, and writes them to the underlying writer.
+///
+/// `finish` must be called to write the final, partially filled byte.
+#[derive(Debug)]
+pub struct BitWriter<W: Write> {
+    writer: W,
+    order: BitOrder,
+    byte: u8,
+    num_bits: u8,
+}
+
+impl<W: Write> BitWriter<W> {
+    pub fn new(writer: W, order: BitOrder) -> Self {
+        Self {
+            writer,
+            order,
+            byte: 0,
+            num_bits: 0,
+        }
+    }
+
+    /// Writes a single bit.
+    pub fn write_bit(&mut self, bit: bool) -> IoResult<()> {
+        if bit {
+            self.byte |= self.order.mask(self.num_bits);
+        }
+        self.num_bits += 1;
+
+        if self.num_bits == 8 {
+            self.writer.write_all(&[self.byte])?;
+            self.byte = 0;
+            self.num_bits = 0;
+        }
+        Ok(())
+    }
+
+    /// Writes each of the given bits, in order.
+    pub fn write_bits(&mut self, bits: &[bool]) -> IoResult<()> {
+        for bit in bits {
+            self.write_bit(*bit)?;
+        }
+        Ok(())
+    }
+
+    /// Writes any partially filled byte, padded with zero bits, and returns the underlying writer.
+    pub fn finish(mut self) -> IoResult<W> {
+        if self.num_bits > 0 {
+            self.writer.write_all(&[self.byte])?;
+        }
+        Ok(self.writer)
+    }
+}
+
+/// Reads bytes from the underlying reader, and unpacks them into bits.
+#[derive(Debug)]
+pub struct BitReader<R: Read> {
+    reader: R,
+    order: BitOrder,
+    byte: u8,
+    num_bits: u8,
+}
+
+impl<R: Read> BitReader<R> {
+    pub fn new(reader: R, order: BitOrder) -> Self {
+        Self {
+            reader,
+            order,
+            byte: 0,
+            num_bits: 8,
+        }
+    }
+
+    /// Reads a single bit.
+    pub fn read_bit(&mut self) -> IoResult<bool> {
+        if self.num_bits == 8 {
+            let mut byte = [0u8];
+            self.reader.read_exact(&mut byte)?;
+            self.byte = byte[0];
+            self.num_bits = 0;
+        }
+
+        let bit = self.byte & self.order.mask(self.num_bits) != 0;
+        self.num_bits += 1;
+        Ok(bit)
+    }
+
+    /// Reads `num_bits` bits.
+    pub fn read_bits(&mut self, num_bits: usize) -> IoResult<Vec<bool>> {
+        (0..num_bits).map(|_| self.read_bit()).collect()
+    }
+
+    /// Discards the remaining bits of a partially read byte, and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Packs `bits` into bytes in the given order, padding the final byte with zero bits.
+pub fn bits_to_bytes(bits: &[bool], order: BitOrder) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, bit)| **bit)
+                .fold(0u8, |byte, (i, _)| byte | order.mask(i as u8))
+        })
+        .collect()
+}
+
+/// Unpacks every bit of `bytes` in the given order.
+pub fn bytes_to_bits(bytes: &[u8], order: BitOrder) -> Vec<bool> {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| byte & order.mask(i) != 0))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        biginteger::{BigInteger, BigInteger256},
+        rand::test_rng,
+    };
+
+    use rand::Rng;
+
+    #[test]
+    fn test_bit_orders() {
+        let bits = [true, false, false, false, false, false, true, true, true];
+        assert_eq!(bits_to_bytes(&bits, BitOrder::MsbFirst), vec![0b1000_0011, 0b1000_0000]);
+        assert_eq!(bits_to_bytes(&bits, BitOrder::LsbFirst), vec![0b1100_0001, 0b0000_0001]);
+
+        assert_eq!(bytes_to_bits(&[0b1000_0011], BitOrder::MsbFirst), &bits[..8]);
+        assert_eq!(bytes_to_bits(&[0b1100_0001], BitOrder::LsbFirst), &bits[..8]);
+    }
+
+    #[test]
+    fn test_writer_and_reader() {
+        let rng = &mut test_rng();
+        for order in [BitOrder::MsbFirst, BitOrder::LsbFirst].iter() {
+            for num_bits in 0..40usize {
+                let bits: Vec<bool> = (0..num_bits).map(|_| rng.gen()).collect();
+
+                let mut writer = BitWriter::new(Vec::new(), *order);
+                writer.write_bits(&bits).unwrap();
+                let bytes = writer.finish().unwrap();
+                assert_eq!(bytes, bits_to_bytes(&bits, *order));
+                assert_eq!(bytes.len(), num_bits.div_ceil(8));
+
+                let mut reader = BitReader::new(&bytes[..], *order);
+                assert_eq!(reader.read_bits(num_bits).unwrap(), bits);
+                if num_bits % 8 == 0 {
+                    assert!(reader.read_bit().is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_biginteger_bits() {
+        let rng = &mut test_rng();
+        let value: BigInteger256 = rng.gen();
+        let bits = value.to_bits();
+
+        let bytes = bits_to_bytes(&bits, BitOrder::MsbFirst);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(
+            BigInteger256::from_bits(&bytes_to_bits(&bytes, BitOrder::MsbFirst)),
+            value
+        );
+    }
+}
@@ -1,17 +1,58 @@
 use crate::{
     error,
     io::{Read, Result as IoResult, Write},
+    length_encoding::{CompactSize, LengthEncoding},
+    limited_reader::LimitedReader,
+    BTreeMap,
+    BTreeSet,
     Vec,
 };
+#[cfg(feature = "std")]
+use crate::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::hash::Hash;
+
+use core::marker::PhantomData;
 
 pub trait ToBytes {
     /// Serializes `self` into `writer`.
     fn write<W: Write>(&self, writer: W) -> IoResult<()>;
+
+    /// Serializes `self` into a new vector.
+    #[inline]
+    fn to_bytes_vec(&self) -> IoResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes)?;
+        Ok(bytes)
+    }
 }
 
 pub trait FromBytes: Sized {
     /// Reads `Self` from `reader`.
     fn read<R: Read>(reader: R) -> IoResult<Self>;
+
+    /// Reads `Self` from `bytes`, failing if any bytes remain afterwards.
+    #[inline]
+    fn from_bytes_exact(mut bytes: &[u8]) -> IoResult<Self> {
+        let value = Self::read(&mut bytes)?;
+        match bytes.is_empty() {
+            true => Ok(value),
+            false => Err(error("FromBytes::from_bytes_exact failed: trailing bytes")),
+        }
+    }
+
+    /// Reads `Self` from `reader`, within the budget of its `DecodeContext`.
+    /// Length-prefixed impls must reserve their elements before allocating.
+    #[inline]
+    fn read_limited<R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+        Self::read(reader)
+    }
+}
+
+pub trait SerializedSize {
+    /// Returns the number of bytes written by `ToBytes::write` for `self`,
+    /// without serializing it.
+    fn serialized_size(&self) -> usize;
 }
 
 macro_rules! array_bytes {
@@ -23,6 +64,13 @@ macro_rules! array_bytes {
             }
         }
 
+        impl SerializedSize for [u8; $N] {
+            #[inline]
+            fn serialized_size(&self) -> usize {
+                $N
+            }
+        }
+
         impl FromBytes for [u8; $N] {
             #[inline]
             fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -42,6 +90,13 @@ macro_rules! array_bytes {
             }
         }
 
+        impl SerializedSize for [u16; $N] {
+            #[inline]
+            fn serialized_size(&self) -> usize {
+                $N * 2
+            }
+        }
+
         impl FromBytes for [u16; $N] {
             #[inline]
             fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -65,6 +120,13 @@ macro_rules! array_bytes {
             }
         }
 
+        impl SerializedSize for [u32; $N] {
+            #[inline]
+            fn serialized_size(&self) -> usize {
+                $N * 4
+            }
+        }
+
         impl FromBytes for [u32; $N] {
             #[inline]
             fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -88,6 +150,13 @@ macro_rules! array_bytes {
             }
         }
 
+        impl SerializedSize for [u64; $N] {
+            #[inline]
+            fn serialized_size(&self) -> usize {
+                $N * 8
+            }
+        }
+
         impl FromBytes for [u64; $N] {
             #[inline]
             fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -168,6 +237,13 @@ impl ToBytes for u8 {
     }
 }
 
+impl SerializedSize for u8 {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        1
+    }
+}
+
 impl FromBytes for u8 {
     #[inline]
     fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -184,6 +260,13 @@ impl ToBytes for u16 {
     }
 }
 
+impl SerializedSize for u16 {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        2
+    }
+}
+
 impl FromBytes for u16 {
     #[inline]
     fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -200,6 +283,13 @@ impl ToBytes for u32 {
     }
 }
 
+impl SerializedSize for u32 {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        4
+    }
+}
+
 impl FromBytes for u32 {
     #[inline]
     fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -216,6 +306,13 @@ impl ToBytes for u64 {
     }
 }
 
+impl SerializedSize for u64 {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        8
+    }
+}
+
 impl FromBytes for u64 {
     #[inline]
     fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -232,6 +329,13 @@ impl ToBytes for i64 {
     }
 }
 
+impl SerializedSize for i64 {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        8
+    }
+}
+
 impl FromBytes for i64 {
     #[inline]
     fn read<R: Read>(mut reader: R) -> IoResult<Self> {
@@ -248,6 +352,13 @@ impl ToBytes for () {
     }
 }
 
+impl SerializedSize for () {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        0
+    }
+}
+
 impl FromBytes for () {
     #[inline]
     fn read<R: Read>(_bytes: R) -> IoResult<Self> {
@@ -262,6 +373,13 @@ impl ToBytes for bool {
     }
 }
 
+impl SerializedSize for bool {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        1
+    }
+}
+
 impl FromBytes for bool {
     #[inline]
     fn read<R: Read>(reader: R) -> IoResult<Self> {
@@ -284,6 +402,13 @@ impl<T: ToBytes> ToBytes for Vec<T> {
     }
 }
 
+impl<T: SerializedSize> SerializedSize for Vec<T> {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.as_slice().serialized_size()
+    }
+}
+
 impl<'a, T: 'a + ToBytes> ToBytes for &'a [T] {
     #[inline]
     fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
@@ -294,6 +419,13 @@ impl<'a, T: 'a + ToBytes> ToBytes for &'a [T] {
     }
 }
 
+impl<'a, T: 'a + SerializedSize> SerializedSize for &'a [T] {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.iter().map(SerializedSize::serialized_size).sum()
+    }
+}
+
 impl<'a, T: 'a + ToBytes> ToBytes for &'a T {
     #[inline]
     fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
@@ -301,15 +433,398 @@ impl<'a, T: 'a + ToBytes> ToBytes for &'a T {
     }
 }
 
+impl<'a, T: 'a + SerializedSize> SerializedSize for &'a T {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        (*self).serialized_size()
+    }
+}
+
+/// Writes the given entries as a count in the length encoding `L`, followed by each
+/// entry in iteration order. Callers are responsible for the ordering of `entries`.
+fn write_entries<L: LengthEncoding, T, W: Write>(
+    entries: impl ExactSizeIterator<Item = T>,
+    mut writer: W,
+    write_entry: impl Fn(T, &mut W) -> IoResult<()>,
+) -> IoResult<()> {
+    L::write_length(entries.len(), &mut writer)?;
+    for entry in entries {
+        write_entry(entry, &mut writer)?;
+    }
+    Ok(())
+}
+
+#[inline]
+fn write_pair<K: ToBytes, V: ToBytes, W: Write>((key, value): (&K, &V), writer: &mut W) -> IoResult<()> {
+    key.write(&mut *writer)?;
+    value.write(writer)
+}
+
+/// Returns the size of the count in the length encoding `L` plus the sizes of the given entries.
+#[inline]
+fn entries_size<L: LengthEncoding, T>(
+    entries: impl ExactSizeIterator<Item = T>,
+    entry_size: impl Fn(T) -> usize,
+) -> usize {
+    L::length_size(entries.len()) + entries.map(entry_size).sum::<usize>()
+}
+
+/// Reads `len` entries, ensuring the keys are strictly increasing. This rejects
+/// both unsorted and duplicate keys, so that every collection has exactly one
+/// valid encoding. `capacity` must only be nonzero for a trusted or reserved `len`.
+fn read_sorted_entries<K: Ord, T, R: Read>(
+    mut reader: R,
+    len: usize,
+    capacity: usize,
+    read_entry: impl Fn(&mut R) -> IoResult<T>,
+    key: impl Fn(&T) -> &K,
+) -> IoResult<Vec<T>> {
+    let mut entries: Vec<T> = Vec::with_capacity(capacity);
+    for _ in 0..len {
+        let entry = read_entry(&mut reader)?;
+        if let Some(previous) = entries.last() {
+            if key(previous) >= key(&entry) {
+                return Err(error("FromBytes::read failed: keys are not strictly increasing"));
+            }
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Reads a count in the length encoding `L` followed by that many entries. The count
+/// is untrusted, so the entries are not preallocated.
+#[inline]
+fn read_entries<L: LengthEncoding, K: Ord, T, R: Read>(
+    mut reader: R,
+    read_entry: impl Fn(&mut R) -> IoResult<T>,
+    key: impl Fn(&T) -> &K,
+) -> IoResult<Vec<T>> {
+    let len = L::read_length(&mut reader)?;
+    read_sorted_entries(reader, len, 0, read_entry, key)
+}
+
+/// Reads a count in the length encoding `L` followed by that many entries, reserving
+/// the count from the decode context before allocating for it.
+#[inline]
+fn read_entries_limited<L: LengthEncoding, K: Ord, T, R: Read>(
+    reader: &mut LimitedReader<R>,
+    read_entry: impl Fn(&mut LimitedReader<R>) -> IoResult<T>,
+    key: impl Fn(&T) -> &K,
+) -> IoResult<Vec<T>> {
+    let len = L::read_length(&mut *reader)?;
+    reader.reserve_elements(len)?;
+    read_sorted_entries(&mut *reader, len, len, |r| read_entry(*r), key)
+}
+
+#[inline]
+fn read_pair<K: FromBytes, V: FromBytes, R: Read>(reader: &mut R) -> IoResult<(K, V)> {
+    Ok((K::read(&mut *reader)?, V::read(reader)?))
+}
+
+#[inline]
+fn read_pair_limited<K: FromBytes, V: FromBytes, R: Read>(reader: &mut LimitedReader<R>) -> IoResult<(K, V)> {
+    Ok((K::read_limited(reader)?, V::read_limited(reader)?))
+}
+
+/// Serializes a collection with its length prefix in a given length encoding.
+///
+/// The elements must also implement `FromBytes`, which requires their encodings to be
+/// self-delimiting. Otherwise distinct collections could have the same encoding, as
+/// `{1: [], 2: [5, 0, 0, 0]}` and `{1: [2, 0, 0, 0], 5: []}` would for `u32` keys and
+/// `Vec<u8>` values.
+pub trait ToBytesWithLength {
+    /// Serializes `self` into `writer`, prefixed by its length in the encoding `L`.
+    fn write_with_length<L: LengthEncoding, W: Write>(&self, writer: W) -> IoResult<()>;
+}
+
+/// Measures a collection with its length prefix in a given length encoding.
+pub trait SerializedSizeWithLength {
+    /// Returns the number of bytes written by `ToBytesWithLength::write_with_length`
+    /// for `self` in the encoding `L`, without serializing it.
+    fn serialized_size_with_length<L: LengthEncoding>(&self) -> usize;
+}
+
+/// Deserializes a collection with its length prefix in a given length encoding.
+pub trait FromBytesWithLength: Sized {
+    /// Reads `Self` from `reader`, prefixed by its length in the encoding `L`.
+    fn read_with_length<L: LengthEncoding, R: Read>(reader: R) -> IoResult<Self>;
+
+    /// Reads `Self` from `reader`, prefixed by its length in the encoding `L`,
+    /// within the budget of its `DecodeContext`.
+    fn read_limited_with_length<L: LengthEncoding, R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self>;
+}
+
+impl<T: ToBytes + FromBytes> ToBytesWithLength for Vec<T> {
+    #[inline]
+    fn write_with_length<L: LengthEncoding, W: Write>(&self, writer: W) -> IoResult<()> {
+        write_entries::<L, _, _>(self.iter(), writer, |t, w| t.write(w))
+    }
+}
+
+impl<T: SerializedSize> SerializedSizeWithLength for Vec<T> {
+    #[inline]
+    fn serialized_size_with_length<L: LengthEncoding>(&self) -> usize {
+        entries_size::<L, _>(self.iter(), SerializedSize::serialized_size)
+    }
+}
+
+impl<T: FromBytes> FromBytesWithLength for Vec<T> {
+    #[inline]
+    fn read_with_length<L: LengthEncoding, R: Read>(mut reader: R) -> IoResult<Self> {
+        // The count is untrusted, so the items are not preallocated.
+        let len = L::read_length(&mut reader)?;
+        (0..len).map(|_| T::read(&mut reader)).collect()
+    }
+
+    #[inline]
+    fn read_limited_with_length<L: LengthEncoding, R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+        let len = L::read_length(&mut *reader)?;
+        reader.reserve_elements(len)?;
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::read_limited(reader)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<K: ToBytes + FromBytes, V: ToBytes + FromBytes> ToBytesWithLength for BTreeMap<K, V> {
+    #[inline]
+    fn write_with_length<L: LengthEncoding, W: Write>(&self, writer: W) -> IoResult<()> {
+        write_entries::<L, _, _>(self.iter(), writer, write_pair)
+    }
+}
+
+impl<K: SerializedSize, V: SerializedSize> SerializedSizeWithLength for BTreeMap<K, V> {
+    #[inline]
+    fn serialized_size_with_length<L: LengthEncoding>(&self) -> usize {
+        entries_size::<L, _>(self.iter(), |(k, v)| k.serialized_size() + v.serialized_size())
+    }
+}
+
+impl<K: FromBytes + Ord, V: FromBytes> FromBytesWithLength for BTreeMap<K, V> {
+    #[inline]
+    fn read_with_length<L: LengthEncoding, R: Read>(reader: R) -> IoResult<Self> {
+        let entries = read_entries::<L, _, _, _>(reader, read_pair, |(k, _)| k)?;
+        Ok(entries.into_iter().collect())
+    }
+
+    #[inline]
+    fn read_limited_with_length<L: LengthEncoding, R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+        let entries = read_entries_limited::<L, _, _, _>(reader, read_pair_limited, |(k, _)| k)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+impl<T: ToBytes + FromBytes> ToBytesWithLength for BTreeSet<T> {
+    #[inline]
+    fn write_with_length<L: LengthEncoding, W: Write>(&self, writer: W) -> IoResult<()> {
+        write_entries::<L, _, _>(self.iter(), writer, |t, w| t.write(w))
+    }
+}
+
+impl<T: SerializedSize> SerializedSizeWithLength for BTreeSet<T> {
+    #[inline]
+    fn serialized_size_with_length<L: LengthEncoding>(&self) -> usize {
+        entries_size::<L, _>(self.iter(), SerializedSize::serialized_size)
+    }
+}
+
+impl<T: FromBytes + Ord> FromBytesWithLength for BTreeSet<T> {
+    #[inline]
+    fn read_with_length<L: LengthEncoding, R: Read>(reader: R) -> IoResult<Self> {
+        let entries = read_entries::<L, _, _, _>(reader, |r| T::read(r), |t| t)?;
+        Ok(entries.into_iter().collect())
+    }
+
+    #[inline]
+    fn read_limited_with_length<L: LengthEncoding, R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+        let entries = read_entries_limited::<L, _, _, _>(reader, T::read_limited, |t| t)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+/// Writes the map in ascending key order, producing the same bytes as the equivalent `BTreeMap`.
+#[cfg(feature = "std")]
+impl<K: ToBytes + FromBytes + Ord, V: ToBytes + FromBytes, S> ToBytesWithLength for HashMap<K, V, S> {
+    #[inline]
+    fn write_with_length<L: LengthEncoding, W: Write>(&self, writer: W) -> IoResult<()> {
+        let mut entries: Vec<(&K, &V)> = self.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        write_entries::<L, _, _>(entries.into_iter(), writer, write_pair)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: SerializedSize, V: SerializedSize, S> SerializedSizeWithLength for HashMap<K, V, S> {
+    #[inline]
+    fn serialized_size_with_length<L: LengthEncoding>(&self) -> usize {
+        entries_size::<L, _>(self.iter(), |(k, v)| k.serialized_size() + v.serialized_size())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: FromBytes + Ord + Hash, V: FromBytes> FromBytesWithLength for HashMap<K, V> {
+    #[inline]
+    fn read_with_length<L: LengthEncoding, R: Read>(reader: R) -> IoResult<Self> {
+        let entries = read_entries::<L, _, _, _>(reader, read_pair, |(k, _)| k)?;
+        Ok(entries.into_iter().collect())
+    }
+
+    #[inline]
+    fn read_limited_with_length<L: LengthEncoding, R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+        let entries = read_entries_limited::<L, _, _, _>(reader, read_pair_limited, |(k, _)| k)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+/// Writes the set in ascending order, producing the same bytes as the equivalent `BTreeSet`.
+#[cfg(feature = "std")]
+impl<T: ToBytes + FromBytes + Ord, S> ToBytesWithLength for HashSet<T, S> {
+    #[inline]
+    fn write_with_length<L: LengthEncoding, W: Write>(&self, writer: W) -> IoResult<()> {
+        let mut entries: Vec<&T> = self.iter().collect();
+        entries.sort_unstable();
+        write_entries::<L, _, _>(entries.into_iter(), writer, |t, w| t.write(w))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: SerializedSize, S> SerializedSizeWithLength for HashSet<T, S> {
+    #[inline]
+    fn serialized_size_with_length<L: LengthEncoding>(&self) -> usize {
+        entries_size::<L, _>(self.iter(), SerializedSize::serialized_size)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: FromBytes + Ord + Hash> FromBytesWithLength for HashSet<T> {
+    #[inline]
+    fn read_with_length<L: LengthEncoding, R: Read>(reader: R) -> IoResult<Self> {
+        let entries = read_entries::<L, _, _, _>(reader, |r| T::read(r), |t| t)?;
+        Ok(entries.into_iter().collect())
+    }
+
+    #[inline]
+    fn read_limited_with_length<L: LengthEncoding, R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+        let entries = read_entries_limited::<L, _, _, _>(reader, T::read_limited, |t| t)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+/// Implements the byte traits for collections, with a `CompactSize` length prefix.
+macro_rules! impl_bytes_with_compact_size {
+    ($($(#[$attr:meta])* [$($generics:tt)*] $ty:ty;)*) => {
+        $(
+            $(#[$attr])*
+            impl<$($generics)*> ToBytes for $ty
+            where
+                $ty: ToBytesWithLength,
+            {
+                #[inline]
+                fn write<W: Write>(&self, writer: W) -> IoResult<()> {
+                    self.write_with_length::<CompactSize, _>(writer)
+                }
+            }
+
+            $(#[$attr])*
+            impl<$($generics)*> SerializedSize for $ty
+            where
+                $ty: SerializedSizeWithLength,
+            {
+                #[inline]
+                fn serialized_size(&self) -> usize {
+                    self.serialized_size_with_length::<CompactSize>()
+                }
+            }
+
+            $(#[$attr])*
+            impl<$($generics)*> FromBytes for $ty
+            where
+                $ty: FromBytesWithLength,
+            {
+                #[inline]
+                fn read<R: Read>(reader: R) -> IoResult<Self> {
+                    Self::read_with_length::<CompactSize, _>(reader)
+                }
+
+                #[inline]
+                fn read_limited<R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+                    Self::read_limited_with_length::<CompactSize, _>(reader)
+                }
+            }
+        )*
+    };
+}
+
+impl_bytes_with_compact_size! {
+    [K, V] BTreeMap<K, V>;
+    [T] BTreeSet<T>;
+    #[cfg(feature = "std")]
+    [K, V, S] HashMap<K, V, S>;
+    #[cfg(feature = "std")]
+    [T, S] HashSet<T, S>;
+}
+
+/// A collection whose length prefix is written in the length encoding `L`, instead
+/// of the default `CompactSize`. The entries are encoded as for the bare collection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LengthPrefixed<C, L: LengthEncoding> {
+    pub inner: C,
+    _encoding: PhantomData<L>,
+}
+
+impl<C, L: LengthEncoding> LengthPrefixed<C, L> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            _encoding: PhantomData,
+        }
+    }
+
+    /// Returns the underlying collection.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: ToBytesWithLength, L: LengthEncoding> ToBytes for LengthPrefixed<C, L> {
+    #[inline]
+    fn write<W: Write>(&self, writer: W) -> IoResult<()> {
+        self.inner.write_with_length::<L, _>(writer)
+    }
+}
+
+impl<C: SerializedSizeWithLength, L: LengthEncoding> SerializedSize for LengthPrefixed<C, L> {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.inner.serialized_size_with_length::<L>()
+    }
+}
+
+impl<C: FromBytesWithLength, L: LengthEncoding> FromBytes for LengthPrefixed<C, L> {
+    #[inline]
+    fn read<R: Read>(reader: R) -> IoResult<Self> {
+        Ok(Self::new(C::read_with_length::<L, _>(reader)?))
+    }
+
+    #[inline]
+    fn read_limited<R: Read>(reader: &mut LimitedReader<R>) -> IoResult<Self> {
+        Ok(Self::new(C::read_limited_with_length::<L, _>(reader)?))
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::ToBytes;
-    use crate::Vec;
+    use super::{FromBytes, ToBytes};
+    use crate::{BTreeMap, BTreeSet, Vec};
+
     #[test]
     fn test_macro_empty() {
         let array: Vec<u8> = vec![];
         let bytes: Vec<u8> = to_bytes![array].unwrap();
-        assert_eq!(&bytes, &[]);
+        assert_eq!(&bytes, &[0u8; 0]);
         assert_eq!(bytes.len(), 0);
     }
 
@@ -327,4 +842,125 @@ mod test {
         actual_bytes.extend_from_slice(&array3);
         assert_eq!(bytes, actual_bytes);
     }
+
+    #[test]
+    fn test_btreemap_roundtrip() {
+        let map: BTreeMap<u32, u64> = (0..300u32).map(|i| (i * 3, u64::from(i) << 20)).collect();
+        let bytes = to_bytes![map].unwrap();
+        assert_eq!(bytes.len(), 3 + 300 * (4 + 8));
+        assert_eq!(map, BTreeMap::<u32, u64>::read(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_btreeset_roundtrip() {
+        let set: BTreeSet<u16> = [7u16, 1, 300, 42].iter().copied().collect();
+        let bytes = to_bytes![set].unwrap();
+        assert_eq!(bytes, vec![4, 1, 0, 7, 0, 42, 0, 44, 1]);
+        assert_eq!(set, BTreeSet::<u16>::read(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_collections_are_unambiguous() {
+        use super::LengthPrefixed;
+        use crate::length_encoding::CompactSize;
+
+        // The values carry their own length, so the entry boundaries cannot shift.
+        type Map = BTreeMap<u32, LengthPrefixed<Vec<u8>, CompactSize>>;
+        let entry = |key: u32, value: &[u8]| (key, LengthPrefixed::new(value.to_vec()));
+        let a: Map = vec![entry(1, &[]), entry(2, &[5, 0, 0, 0])].into_iter().collect();
+        let b: Map = vec![entry(1, &[2, 0, 0, 0]), entry(5, &[])].into_iter().collect();
+
+        let (a_bytes, b_bytes) = (to_bytes![a].unwrap(), to_bytes![b].unwrap());
+        assert_ne!(a_bytes, b_bytes);
+        assert_eq!(Map::from_bytes_exact(&a_bytes).unwrap(), a);
+        assert_eq!(Map::from_bytes_exact(&b_bytes).unwrap(), b);
+    }
+
+    #[test]
+    fn test_collections_reject_unsorted_keys() {
+        let unsorted = [2u8, 5, 3];
+        assert!(BTreeSet::<u8>::read(&unsorted[..]).is_err());
+        assert!(BTreeMap::<u8, bool>::read(&[2u8, 5, 1, 3, 0][..]).is_err());
+    }
+
+    #[test]
+    fn test_collections_reject_duplicate_keys() {
+        let duplicate = [2u8, 3, 3];
+        assert!(BTreeSet::<u8>::read(&duplicate[..]).is_err());
+        assert!(BTreeMap::<u8, bool>::read(&[2u8, 3, 1, 3, 0][..]).is_err());
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        use crate::io::ErrorKind;
+
+        assert_eq!(bool::read(&[2u8][..]).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(u32::read(&[0u8; 3][..]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(0u32.write(&mut [0u8; 3][..]).unwrap_err().kind(), ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_to_bytes_vec() {
+        let array = [0x0102_0304u32; 3];
+        assert_eq!(array.to_bytes_vec().unwrap(), to_bytes![array].unwrap());
+        assert_eq!(().to_bytes_vec().unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_from_bytes_exact() {
+        assert_eq!(u16::from_bytes_exact(&[0x34, 0x12]).unwrap(), 0x1234);
+        assert!(u16::from_bytes_exact(&[0x34]).is_err());
+        assert!(u16::from_bytes_exact(&[0x34, 0x12, 0x00]).is_err());
+        assert!(<()>::from_bytes_exact(&[]).is_ok());
+        assert!(<()>::from_bytes_exact(&[0]).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hash_collections_are_canonical() {
+        use crate::{HashMap, HashSet};
+
+        let map: HashMap<u64, u8> = (0..64u64)
+            .map(|i| (i.wrapping_mul(0x9e37_79b9_7f4a_7c15), i as u8))
+            .collect();
+        let btree_map: BTreeMap<u64, u8> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        let bytes = to_bytes![map].unwrap();
+        assert_eq!(bytes, to_bytes![btree_map].unwrap());
+        assert_eq!(map, HashMap::<u64, u8>::read(&bytes[..]).unwrap());
+
+        let set: HashSet<u64> = map.keys().copied().collect();
+        let btree_set: BTreeSet<u64> = set.iter().copied().collect();
+        let bytes = to_bytes![set].unwrap();
+        assert_eq!(bytes, to_bytes![btree_set].unwrap());
+        assert_eq!(set, HashSet::<u64>::read(&bytes[..]).unwrap());
+        assert!(HashSet::<u8>::read(&[2u8, 9, 9][..]).is_err());
+    }
+
+    #[test]
+    fn test_length_prefixed() {
+        use super::{LengthPrefixed, SerializedSize};
+        use crate::length_encoding::{CompactSize, FixedU32, Leb128};
+
+        let set: BTreeSet<u16> = (0..300u16).collect();
+        let compact = LengthPrefixed::<_, CompactSize>::new(set.clone());
+        assert_eq!(to_bytes![compact].unwrap(), to_bytes![set].unwrap());
+
+        let leb128 = LengthPrefixed::<_, Leb128>::new(set.clone());
+        let bytes = to_bytes![leb128].unwrap();
+        assert_eq!(&bytes[..2], &[0xac, 0x02]);
+        assert_eq!(bytes.len(), leb128.serialized_size());
+        assert_eq!(
+            LengthPrefixed::<BTreeSet<u16>, Leb128>::from_bytes_exact(&bytes).unwrap(),
+            leb128
+        );
+
+        let items: Vec<u8> = vec![3, 1, 2];
+        let fixed = LengthPrefixed::<_, FixedU32>::new(items);
+        let bytes = to_bytes![fixed].unwrap();
+        assert_eq!(bytes, [3, 0, 0, 0, 3, 1, 2]);
+        assert_eq!(
+            LengthPrefixed::<Vec<u8>, FixedU32>::from_bytes_exact(&bytes).unwrap(),
+            fixed
+        );
+    }
 }
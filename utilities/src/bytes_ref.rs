@@ -0,0 +1,255 @@
+use crate::{
+    bytes::LengthPrefixed,
+    error,
+    io::Result as IoResult,
+    length_encoding::{CompactSize, LengthEncoding},
+};
+
+use core::{convert::TryInto, mem};
+
+pub trait FromBytesRef<'a>: Sized {
+    /// Reads `Self` from the front of `input`, borrowing from it rather than copying,
+    /// and advances `input` past the bytes that were read.
+    fn read_ref(input: &mut &'a [u8]) -> IoResult<Self>;
+}
+
+/// Splits `num_bytes` off the front of `input`.
+#[inline]
+fn take_bytes<'a>(input: &mut &'a [u8], num_bytes: usize) -> IoResult<&'a [u8]> {
+    if num_bytes > input.len() {
+        return Err(error("FromBytesRef::read_ref failed: unexpected end of input"));
+    }
+    let (bytes, rest) = input.split_at(num_bytes);
+    *input = rest;
+    Ok(bytes)
+}
+
+macro_rules! from_bytes_ref_int {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a> FromBytesRef<'a> for $ty {
+                #[inline]
+                fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+                    let bytes = take_bytes(input, mem::size_of::<$ty>())?;
+                    Ok(<$ty>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+from_bytes_ref_int!(u8, u16, u32, u64, i64);
+
+impl<'a> FromBytesRef<'a> for bool {
+    #[inline]
+    fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+        match u8::read_ref(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(error("FromBytesRef::read_ref failed")),
+        }
+    }
+}
+
+impl<'a> FromBytesRef<'a> for () {
+    #[inline]
+    fn read_ref(_input: &mut &'a [u8]) -> IoResult<Self> {
+        Ok(())
+    }
+}
+
+macro_rules! from_bytes_ref_array {
+    ($($N:expr),*) => {
+        $(
+            impl<'a> FromBytesRef<'a> for &'a [u8; $N] {
+                #[inline]
+                fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+                    Ok(take_bytes(input, $N)?.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+from_bytes_ref_array!(
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30,
+    31, 32
+);
+
+/// Reads a length in the encoding `L`, followed by that many bytes.
+#[inline]
+fn read_bytes_ref<'a, L: LengthEncoding>(input: &mut &'a [u8]) -> IoResult<&'a [u8]> {
+    let len = L::read_length(&mut *input)?;
+    take_bytes(input, len)
+}
+
+/// Reads a length in the encoding `L`, followed by that many little endian `u64`s.
+fn read_u64s_ref<'a, L: LengthEncoding>(input: &mut &'a [u8]) -> IoResult<&'a [u64]> {
+    let len = L::read_length(&mut *input)?;
+    let num_bytes = len
+        .checked_mul(mem::size_of::<u64>())
+        .ok_or_else(|| error("FromBytesRef::read_ref failed: length overflow"))?;
+
+    // An empty slice needs no bytes, so it is valid wherever the input sits in memory.
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if cfg!(target_endian = "big") {
+        return Err(error("FromBytesRef::read_ref failed: big endian target"));
+    }
+    if input.as_ptr().align_offset(mem::align_of::<u64>()) != 0 {
+        return Err(error("FromBytesRef::read_ref failed: unaligned input"));
+    }
+    let bytes = take_bytes(input, num_bytes)?;
+
+    // Safety: `bytes` is valid for `len * 8` bytes and lives for `'a`, is aligned for `u64`,
+    // every bit pattern is a valid `u64`, and the target byte order is little endian.
+    Ok(unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const u64, len) })
+}
+
+/// Reads a variable length integer length, followed by that many bytes.
+impl<'a> FromBytesRef<'a> for &'a [u8] {
+    #[inline]
+    fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+        read_bytes_ref::<CompactSize>(input)
+    }
+}
+
+/// Reads a variable length integer length, followed by that many little endian `u64`s.
+/// Fails unless the `u64`s are 8-byte aligned in memory, and the target is little endian.
+impl<'a> FromBytesRef<'a> for &'a [u64] {
+    #[inline]
+    fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+        read_u64s_ref::<CompactSize>(input)
+    }
+}
+
+/// Reads a length in the encoding `L`, followed by that many bytes.
+impl<'a, L: LengthEncoding> FromBytesRef<'a> for LengthPrefixed<&'a [u8], L> {
+    #[inline]
+    fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+        Ok(Self::new(read_bytes_ref::<L>(input)?))
+    }
+}
+
+/// Reads a length in the encoding `L`, followed by that many little endian `u64`s,
+/// with the same alignment and byte order requirements as for `&[u64]`.
+impl<'a, L: LengthEncoding> FromBytesRef<'a> for LengthPrefixed<&'a [u64], L> {
+    #[inline]
+    fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+        Ok(Self::new(read_u64s_ref::<L>(input)?))
+    }
+}
+
+macro_rules! from_bytes_ref_tuple {
+    ($($ty:ident),+) => {
+        impl<'a, $($ty: FromBytesRef<'a>),+> FromBytesRef<'a> for ($($ty,)+) {
+            #[inline]
+            fn read_ref(input: &mut &'a [u8]) -> IoResult<Self> {
+                Ok(($($ty::read_ref(input)?,)+))
+            }
+        }
+    };
+}
+
+from_bytes_ref_tuple!(A);
+from_bytes_ref_tuple!(A, B);
+from_bytes_ref_tuple!(A, B, C);
+from_bytes_ref_tuple!(A, B, C, D);
+from_bytes_ref_tuple!(A, B, C, D, E);
+from_bytes_ref_tuple!(A, B, C, D, E, F);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        length_encoding::{FixedU32, Leb128},
+        variable_length_integer::variable_length_integer,
+    };
+
+    #[test]
+    fn test_read_primitives() {
+        let bytes = [1u8, 0x34, 0x12, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 9];
+        let mut input = &bytes[..];
+        assert!(bool::read_ref(&mut input).unwrap());
+        assert_eq!(u16::read_ref(&mut input).unwrap(), 0x1234);
+        assert_eq!(i64::read_ref(&mut input).unwrap(), -1);
+        assert_eq!(input, &[9]);
+        assert!(u16::read_ref(&mut input).is_err());
+        assert!(bool::read_ref(&mut &[2u8][..]).is_err());
+    }
+
+    #[test]
+    fn test_read_borrowed_bytes() {
+        let mut bytes = variable_length_integer(300);
+        bytes.extend((0..300u32).map(|i| i as u8));
+        bytes.extend_from_slice(&[7, 8, 9]);
+
+        let mut input = &bytes[..];
+        let (slice, array) = <(&[u8], &[u8; 2])>::read_ref(&mut input).unwrap();
+        assert_eq!(slice.len(), 300);
+        assert_eq!(slice.as_ptr(), bytes[3..].as_ptr());
+        assert_eq!(array, &[7, 8]);
+        assert_eq!(input, &[9]);
+
+        // A length prefix beyond the input must not be trusted.
+        assert!(<&[u8]>::read_ref(&mut &variable_length_integer(u64::MAX)[..]).is_err());
+        assert!(<&[u8]>::read_ref(&mut &[4u8, 1, 2, 3][..]).is_err());
+    }
+
+    #[test]
+    fn test_read_borrowed_limbs() {
+        let limbs = [1u64, u64::MAX, 0x0102_0304_0506_0708];
+        let mut encoded = variable_length_integer(limbs.len() as u64);
+        encoded.extend(limbs.iter().flat_map(|limb| limb.to_le_bytes().to_vec()));
+
+        // Place the limbs, which follow the one byte length, at an 8-byte aligned address.
+        let mut buffer = vec![0u8; encoded.len() + 16];
+        let start = (buffer.as_ptr().align_offset(8) + 7) % 8;
+        buffer[start..start + encoded.len()].copy_from_slice(&encoded);
+
+        let mut input = &buffer[start..start + encoded.len()];
+        assert_eq!(<&[u64]>::read_ref(&mut input).unwrap(), &limbs);
+        assert!(input.is_empty());
+
+        // Shifting the limbs by one byte misaligns them.
+        buffer[start + 1..start + 1 + encoded.len()].copy_from_slice(&encoded);
+        let mut input = &buffer[start + 1..start + 1 + encoded.len()];
+        assert!(<&[u64]>::read_ref(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_read_empty_limbs_at_any_alignment() {
+        let buffer = [0u8; 16];
+        for start in 0..8 {
+            // A zero length, followed by the end of the input.
+            let mut input = &buffer[start..start + 1];
+            assert_eq!(<&[u64]>::read_ref(&mut input).unwrap(), &[] as &[u64]);
+            assert!(input.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_read_length_prefixed() {
+        let mut bytes = vec![0xac, 0x02];
+        bytes.extend((0..300u32).map(|i| i as u8));
+        let mut input = &bytes[..];
+        let slice = LengthPrefixed::<&[u8], Leb128>::read_ref(&mut input)
+            .unwrap()
+            .into_inner();
+        assert_eq!(slice.as_ptr(), bytes[2..].as_ptr());
+        assert!(input.is_empty());
+
+        // Place the limbs, which follow the four byte length, at an 8-byte aligned address.
+        let mut buffer = [0u8; 32];
+        let start = (buffer.as_ptr().align_offset(8) + 4) % 8;
+        buffer[start..start + 4].copy_from_slice(&[2, 0, 0, 0]);
+        buffer[start + 4..start + 12].copy_from_slice(&7u64.to_le_bytes());
+        buffer[start + 12..start + 20].copy_from_slice(&9u64.to_le_bytes());
+
+        let mut input = &buffer[start..start + 20];
+        let limbs = LengthPrefixed::<&[u64], FixedU32>::read_ref(&mut input).unwrap();
+        assert_eq!(limbs.into_inner(), &[7, 9]);
+        assert!(input.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+//! Text encodings for any `ToBytes` and `FromBytes` type.
+
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    io::{Error, ErrorKind, Result as IoResult},
+    Vec,
+};
+
+use bech32::{FromBase32, ToBase32, Variant};
+use sha2::{Digest, Sha256};
+
+/// The number of checksum bytes appended to a base58check payload.
+const BASE58_CHECKSUM_LENGTH: usize = 4;
+
+fn invalid_data<E: core::fmt::Display>(error: E) -> Error {
+    Error::new(ErrorKind::InvalidData, error.to_string())
+}
+
+/// Returns the first four bytes of the double SHA-256 of the given payload.
+fn base58_checksum(payload: &[u8]) -> [u8; BASE58_CHECKSUM_LENGTH] {
+    let digest = Sha256::digest(&Sha256::digest(payload));
+    let mut checksum = [0u8; BASE58_CHECKSUM_LENGTH];
+    checksum.copy_from_slice(&digest[..BASE58_CHECKSUM_LENGTH]);
+    checksum
+}
+
+/// Returns the lowercase hex encoding of `value`.
+pub fn to_hex<T: ToBytes>(value: &T) -> IoResult<String> {
+    Ok(hex::encode(value.to_bytes_vec()?))
+}
+
+/// Decodes `T` from a hex string, which must contain exactly one value.
+pub fn from_hex<T: FromBytes>(string: &str) -> IoResult<T> {
+    T::from_bytes_exact(&hex::decode(string).map_err(invalid_data)?)
+}
+
+/// Returns the base58check encoding of `value`, with a double SHA-256 checksum.
+pub fn to_base58check<T: ToBytes>(value: &T) -> IoResult<String> {
+    let mut bytes = value.to_bytes_vec()?;
+    let checksum = base58_checksum(&bytes);
+    bytes.extend_from_slice(&checksum);
+    Ok(bs58::encode(bytes).into_string())
+}
+
+/// Decodes `T` from a base58check string, verifying its checksum.
+pub fn from_base58check<T: FromBytes>(string: &str) -> IoResult<T> {
+    let bytes = bs58::decode(string).into_vec().map_err(invalid_data)?;
+    if bytes.len() < BASE58_CHECKSUM_LENGTH {
+        return Err(invalid_data("base58check string is too short"));
+    }
+
+    let (payload, checksum) = bytes.split_at(bytes.len() - BASE58_CHECKSUM_LENGTH);
+    if base58_checksum(payload) != checksum {
+        return Err(invalid_data("invalid base58check checksum"));
+    }
+    T::from_bytes_exact(payload)
+}
+
+fn encode_bech32<T: ToBytes>(hrp: &str, value: &T, variant: Variant) -> IoResult<String> {
+    bech32::encode(hrp, value.to_bytes_vec()?.to_base32(), variant).map_err(invalid_data)
+}
+
+fn decode_bech32<T: FromBytes>(expected_hrp: &str, string: &str, expected_variant: Variant) -> IoResult<T> {
+    let (hrp, data, variant) = bech32::decode(string).map_err(invalid_data)?;
+    if hrp != expected_hrp {
+        return Err(invalid_data(format!(
+            "invalid human-readable part: expected {}, found {}",
+            expected_hrp, hrp
+        )));
+    }
+    if variant != expected_variant {
+        return Err(invalid_data(format!(
+            "invalid bech32 variant: expected {:?}",
+            expected_variant
+        )));
+    }
+    T::from_bytes_exact(&Vec::<u8>::from_base32(&data).map_err(invalid_data)?)
+}
+
+/// Returns the bech32 encoding of `value` with the given human-readable part.
+pub fn to_bech32<T: ToBytes>(hrp: &str, value: &T) -> IoResult<String> {
+    encode_bech32(hrp, value, Variant::Bech32)
+}
+
+/// Decodes `T` from a bech32 string, verifying its checksum and human-readable part.
+pub fn from_bech32<T: FromBytes>(hrp: &str, string: &str) -> IoResult<T> {
+    decode_bech32(hrp, string, Variant::Bech32)
+}
+
+/// Returns the bech32m encoding of `value` with the given human-readable part.
+pub fn to_bech32m<T: ToBytes>(hrp: &str, value: &T) -> IoResult<String> {
+    encode_bech32(hrp, value, Variant::Bech32m)
+}
+
+/// Decodes `T` from a bech32m string, verifying its checksum and human-readable part.
+pub fn from_bech32m<T: FromBytes>(hrp: &str, string: &str) -> IoResult<T> {
+    decode_bech32(hrp, string, Variant::Bech32m)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const VALUE: [u8; 8] = [0xde, 0xad, 0xbe, 0xef, 0x00, 0x01, 0x02, 0x03];
+
+    #[test]
+    fn test_hex() {
+        let string = to_hex(&VALUE).unwrap();
+        assert_eq!(string, "deadbeef00010203");
+        assert_eq!(from_hex::<[u8; 8]>(&string).unwrap(), VALUE);
+        assert_eq!(from_hex::<u32>("efbeadde").unwrap(), 0xdeadbeef);
+
+        assert!(from_hex::<[u8; 8]>("deadbeef").is_err());
+        assert!(from_hex::<[u8; 8]>("deadbeef0001020304").is_err());
+        assert!(from_hex::<[u8; 8]>("zz").is_err());
+    }
+
+    #[test]
+    fn test_base58check() {
+        let string = to_base58check(&VALUE).unwrap();
+        assert_eq!(from_base58check::<[u8; 8]>(&string).unwrap(), VALUE);
+
+        // Flipping a payload bit must fail the checksum.
+        let mut bytes = bs58::decode(&string).into_vec().unwrap();
+        bytes[0] ^= 1;
+        let error = from_base58check::<[u8; 8]>(&bs58::encode(bytes).into_string()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(from_base58check::<[u8; 8]>("1").is_err());
+    }
+
+    #[test]
+    fn test_bech32() {
+        let string = to_bech32("test", &VALUE).unwrap();
+        assert!(string.starts_with("test1"));
+        assert_eq!(from_bech32::<[u8; 8]>("test", &string).unwrap(), VALUE);
+        assert!(from_bech32::<[u8; 8]>("other", &string).is_err());
+        assert!(from_bech32m::<[u8; 8]>("test", &string).is_err());
+
+        let string = to_bech32m("test", &VALUE).unwrap();
+        assert_eq!(from_bech32m::<[u8; 8]>("test", &string).unwrap(), VALUE);
+        assert!(from_bech32::<[u8; 8]>("test", &string).is_err());
+
+        // Corrupting a character must fail the checksum.
+        let mut corrupted = string.into_bytes();
+        let last = corrupted.len() - 1;
+        corrupted[last] = if corrupted[last] == b'q' { b'p' } else { b'q' };
+        assert!(from_bech32m::<[u8; 8]>("test", std::str::from_utf8(&corrupted).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! A self-describing envelope for persisted artifacts, such as proving keys
+//! and CRH parameters.
+//!
+//! An envelope is laid out as follows, with integers in little endian:
+//!
+//! | field    | size     | contents                                        |
+//! |----------|----------|-------------------------------------------------|
+//! | magic    | 4        | `ENVELOPE_MAGIC`                                |
+//! | type tag | 4        | identifies the type of the payload              |
+//! | version  | 2        | the format version of the payload               |
+//! | length   | 8        | the number of payload bytes                     |
+//! | payload  | `length` | the `ToBytes` serialization of the payload      |
+//! | checksum | 32       | SHA-256 of all of the preceding bytes           |
+
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    io::{Error, ErrorKind, Read, Result as IoResult, Write},
+    sinks::ByteCounter,
+    Vec,
+};
+
+use sha2::{Digest, Sha256};
+
+/// The bytes that begin every envelope.
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"SVMA";
+
+/// The number of bytes in the checksum that ends every envelope.
+const CHECKSUM_LENGTH: usize = 32;
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Describes the payload of an envelope. Readers supply the header they expect,
+/// and reject envelopes with any other header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EnvelopeHeader {
+    pub type_tag: [u8; 4],
+    pub version: u16,
+}
+
+impl EnvelopeHeader {
+    pub const fn new(type_tag: [u8; 4], version: u16) -> Self {
+        Self { type_tag, version }
+    }
+}
+
+/// A writer that forwards all bytes to `inner` while hashing them.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let num_bytes = self.inner.write(buf)?;
+        self.hasher.update(&buf[..num_bytes]);
+        Ok(num_bytes)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes `payload` into `writer`, wrapped in an envelope with the given header.
+/// The payload is streamed, and is never buffered in memory.
+pub fn write_envelope<T: ToBytes, W: Write>(writer: W, header: EnvelopeHeader, payload: &T) -> IoResult<()> {
+    let length = ByteCounter::measure(payload)? as u64;
+
+    let mut writer = HashingWriter {
+        inner: writer,
+        hasher: Sha256::new(),
+    };
+    ENVELOPE_MAGIC.write(&mut writer)?;
+    header.type_tag.write(&mut writer)?;
+    header.version.write(&mut writer)?;
+    length.write(&mut writer)?;
+    payload.write(&mut writer)?;
+
+    let checksum = writer.hasher.finalize();
+    writer.inner.write_all(&checksum)
+}
+
+/// Reads a `T` from an envelope in `reader`, rejecting envelopes that do not
+/// match the `expected` header or fail the checksum.
+pub fn read_envelope<T: FromBytes, R: Read>(mut reader: R, expected: EnvelopeHeader) -> IoResult<T> {
+    let magic = <[u8; 4]>::read(&mut reader)?;
+    if magic != ENVELOPE_MAGIC {
+        return Err(invalid_data("envelope: invalid magic bytes"));
+    }
+    let type_tag = <[u8; 4]>::read(&mut reader)?;
+    if type_tag != expected.type_tag {
+        return Err(invalid_data("envelope: mismatched type tag"));
+    }
+    let version = u16::read(&mut reader)?;
+    if version != expected.version {
+        return Err(invalid_data("envelope: mismatched format version"));
+    }
+    let length = u64::read(&mut reader)?;
+
+    // The length is untrusted, so the payload buffer grows only as bytes arrive.
+    let mut payload = Vec::new();
+    (&mut reader).take(length).read_to_end(&mut payload)?;
+    if payload.len() as u64 != length {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "envelope: truncated payload"));
+    }
+
+    let checksum = <[u8; CHECKSUM_LENGTH]>::read(&mut reader)?;
+    let mut hasher = Sha256::new();
+    hasher.update(magic);
+    hasher.update(type_tag);
+    hasher.update(version.to_le_bytes());
+    hasher.update(length.to_le_bytes());
+    hasher.update(&payload);
+    if hasher.finalize()[..] != checksum[..] {
+        return Err(invalid_data("envelope: invalid checksum"));
+    }
+
+    T::from_bytes_exact(&payload)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::BTreeMap;
+
+    const HEADER: EnvelopeHeader = EnvelopeHeader::new(*b"test", 3);
+
+    fn payload() -> BTreeMap<u32, u64> {
+        (0..100u32).map(|i| (i, u64::from(i) * 7)).collect()
+    }
+
+    fn envelope() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_envelope(&mut bytes, HEADER, &payload()).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let bytes = envelope();
+        assert_eq!(&bytes[..4], &ENVELOPE_MAGIC);
+        assert_eq!(bytes.len(), 18 + to_bytes![payload()].unwrap().len() + CHECKSUM_LENGTH);
+        assert_eq!(
+            read_envelope::<BTreeMap<u32, u64>, _>(&bytes[..], HEADER).unwrap(),
+            payload()
+        );
+    }
+
+    #[test]
+    fn test_envelope_rejects_mismatched_header() {
+        let bytes = envelope();
+        let other_tag = EnvelopeHeader::new(*b"tset", 3);
+        let other_version = EnvelopeHeader::new(*b"test", 4);
+        assert!(read_envelope::<BTreeMap<u32, u64>, _>(&bytes[..], other_tag).is_err());
+        assert!(read_envelope::<BTreeMap<u32, u64>, _>(&bytes[..], other_version).is_err());
+
+        let mut bad_magic = bytes;
+        bad_magic[0] ^= 1;
+        assert!(read_envelope::<BTreeMap<u32, u64>, _>(&bad_magic[..], HEADER).is_err());
+    }
+
+    #[test]
+    fn test_envelope_rejects_corruption() {
+        let bytes = envelope();
+        for i in 4..bytes.len() {
+            let mut corrupted = bytes.clone();
+            corrupted[i] ^= 0x10;
+            assert!(read_envelope::<BTreeMap<u32, u64>, _>(&corrupted[..], HEADER).is_err());
+        }
+        for length in 0..bytes.len() {
+            assert!(read_envelope::<BTreeMap<u32, u64>, _>(&bytes[..length], HEADER).is_err());
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    bytes::ToBytes,
+    io::Result as IoResult,
+    sinks::{Blake2sWriter, ByteCounter},
+    variable_length_integer::VarInt,
+};
+
+use rand::{CryptoRng, Error as RandError, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+/// A deterministic RNG for the Fiat-Shamir transform. Values are absorbed into a BLAKE2s
+/// transcript, and the current transcript digest keys a ChaCha20 stream. Absorbing a value
+/// rekeys the stream, so all randomness drawn afterwards depends on everything absorbed so far.
+#[derive(Clone, Debug)]
+pub struct FiatShamirRng {
+    seed: [u8; 32],
+    rng: ChaChaRng,
+}
+
+impl FiatShamirRng {
+    /// Returns a new RNG for the protocol identified by `domain`. Distinct domains
+    /// produce independent streams, even after absorbing identical values.
+    pub fn new(domain: &[u8]) -> Self {
+        let mut rng = Self {
+            seed: [0u8; 32],
+            rng: ChaChaRng::from_seed([0u8; 32]),
+        };
+        rng.absorb(&domain)
+            .expect("FiatShamirRng: writing to a hasher cannot fail");
+        rng
+    }
+
+    /// Absorbs the serialization of `value` into the transcript.
+    pub fn absorb<T: ToBytes>(&mut self, value: &T) -> IoResult<()> {
+        let mut writer = Blake2sWriter::new();
+        self.seed.write(&mut writer)?;
+        // Length-prefix the value, so that the boundaries between absorbed values are unambiguous.
+        VarInt(ByteCounter::measure(value)? as u64).write(&mut writer)?;
+        value.write(&mut writer)?;
+
+        self.seed.copy_from_slice(&writer.finalize());
+        self.rng = ChaChaRng::from_seed(self.seed);
+        Ok(())
+    }
+}
+
+impl RngCore for FiatShamirRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RandError> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for FiatShamirRng {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rand::UniformRand;
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = FiatShamirRng::new(b"test");
+        let mut b = FiatShamirRng::new(b"test");
+        a.absorb(&[1u64, 2, 3]).unwrap();
+        b.absorb(&[1u64, 2, 3]).unwrap();
+        assert_eq!(<[u64; 4]>::rand(&mut a), <[u64; 4]>::rand(&mut b));
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let mut a = FiatShamirRng::new(b"protocol a");
+        let mut b = FiatShamirRng::new(b"protocol b");
+        a.absorb(&7u32).unwrap();
+        b.absorb(&7u32).unwrap();
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_absorb_changes_stream() {
+        let mut a = FiatShamirRng::new(b"test");
+        let mut b = a.clone();
+        b.absorb(&0u8).unwrap();
+        assert_ne!(a.next_u64(), b.next_u64());
+
+        // The boundaries between absorbed values matter.
+        let mut a = FiatShamirRng::new(b"test");
+        let mut b = FiatShamirRng::new(b"test");
+        a.absorb(&[1u8, 2]).unwrap();
+        a.absorb(&[3u8]).unwrap();
+        b.absorb(&[1u8]).unwrap();
+        b.absorb(&[2u8, 3]).unwrap();
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+}
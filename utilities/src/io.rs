@@ -1,14 +1,139 @@
 //! no-std io replacement
 use crate::Vec;
-use core::{cmp, mem};
+use core::{cmp, fmt, mem};
 
-#[derive(Debug)]
-pub struct Error;
+/// A list specifying general categories of I/O error, mirroring `std::io::ErrorKind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The reader was exhausted before the expected number of bytes were read.
+    UnexpectedEof,
+    /// The data read was not valid for the type being decoded.
+    InvalidData,
+    /// The writer accepted fewer bytes than were written to it.
+    WriteZero,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::UnexpectedEof => "unexpected end of file",
+            ErrorKind::InvalidData => "invalid data",
+            ErrorKind::WriteZero => "write zero",
+            ErrorKind::Other => "other error",
+        }
+    }
+}
+
+/// The error type for no-std I/O operations, mirroring `std::io::Error`
+/// with a static message in place of an arbitrary payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    message: Option<&'static str>,
+}
+
+impl Error {
+    pub const fn new(kind: ErrorKind, message: &'static str) -> Self {
+        Self {
+            kind,
+            message: Some(message),
+        }
+    }
+
+    /// Returns the category of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the message describing this error, or the description of its kind if it has none.
+    pub fn message(&self) -> &'static str {
+        self.message.unwrap_or_else(|| self.kind.as_str())
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind, message: None }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.message {
+            Some(message) => write!(f, "{}: {}", self.kind.as_str(), message),
+            None => write!(f, "{}", self.kind.as_str()),
+        }
+    }
+}
 
 pub type Result<T> = core::result::Result<T, Error>;
 
 pub trait Read {
-    fn read_exact(&mut self, data: &mut [u8]) -> Result<()>;
+    /// Pulls some bytes from this source into `buf`, returning how many bytes were read.
+    /// A return value of `0` indicates that the source is exhausted, or `buf` is empty.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Reads exactly enough bytes to fill `buf`.
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read(buf)? {
+                0 => return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer")),
+                n => buf = &mut buf[n..],
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads all bytes until the source is exhausted, appending them to `buf`.
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        let start = buf.len();
+        let mut chunk = [0u8; 256];
+        loop {
+            match self.read(&mut chunk)? {
+                0 => return Ok(buf.len() - start),
+                n => buf.extend_from_slice(&chunk[..n]),
+            }
+        }
+    }
+
+    /// Returns a mutable reference to this reader.
+    fn by_ref(&mut self) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Returns a reader that reads at most `limit` bytes from this reader.
+    fn take(self, limit: u64) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take { inner: self, limit }
+    }
+
+    /// Returns a reader that reads from this reader until it is exhausted, and then from `next`.
+    fn chain<R: Read>(self, next: R) -> Chain<Self, R>
+    where
+        Self: Sized,
+    {
+        Chain {
+            first: self,
+            second: next,
+            done_first: false,
+        }
+    }
+}
+
+/// A `Read` with an internal buffer, mirroring `std::io::BufRead`.
+pub trait BufRead: Read {
+    /// Returns the contents of the internal buffer, filling it from the source if it is empty.
+    fn fill_buf(&mut self) -> Result<&[u8]>;
+
+    /// Marks `amt` bytes of the internal buffer as read.
+    fn consume(&mut self, amt: usize);
 }
 
 pub trait Write {
@@ -16,16 +141,40 @@ pub trait Write {
 }
 
 impl<R: Read + ?Sized> Read for &mut R {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (**self).read(buf)
+    }
+
     #[inline]
     fn read_exact(&mut self, data: &mut [u8]) -> Result<()> {
         (**self).read_exact(data)
     }
 }
 
+impl<B: BufRead + ?Sized> BufRead for &mut B {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        (**self).fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+}
+
 impl Read for &[u8] {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let amt = cmp::min(buf.len(), self.len());
+        self.read_exact(&mut buf[..amt])?;
+        Ok(amt)
+    }
+
     fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
         if buf.len() > self.len() {
-            return Err(Error);
+            return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
         }
         let (a, b) = self.split_at(buf.len());
 
@@ -43,6 +192,204 @@ impl Read for &[u8] {
     }
 }
 
+impl BufRead for &[u8] {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        Ok(*self)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt..];
+    }
+}
+
+/// Wraps an in-memory buffer to provide it with a position, mirroring `std::io::Cursor`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cursor<T> {
+    inner: T,
+    position: u64,
+}
+
+impl<T> Cursor<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    /// Returns the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the current position of this cursor.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: u64) {
+        self.position = position;
+    }
+}
+
+impl<T: AsRef<[u8]>> Cursor<T> {
+    /// Returns the bytes of the buffer after the current position.
+    fn remaining_slice(&self) -> &[u8] {
+        let buf = self.inner.as_ref();
+        let start = cmp::min(self.position, buf.len() as u64) as usize;
+        &buf[start..]
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for Cursor<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let amt = self.remaining_slice().read(buf)?;
+        self.position += amt as u64;
+        Ok(amt)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.remaining_slice().read_exact(buf)?;
+        self.position += buf.len() as u64;
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]>> BufRead for Cursor<T> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        Ok(self.remaining_slice())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position += amt as u64;
+    }
+}
+
+/// Reads at most a limited number of bytes from the underlying reader, mirroring `std::io::Take`.
+#[derive(Debug)]
+pub struct Take<T> {
+    inner: T,
+    limit: u64,
+}
+
+impl<T> Take<T> {
+    /// Returns the number of bytes that may still be read.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn set_limit(&mut self, limit: u64) {
+        self.limit = limit;
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T: Read> Read for Take<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.limit == 0 {
+            return Ok(0);
+        }
+
+        let max = cmp::min(buf.len() as u64, self.limit) as usize;
+        let amt = self.inner.read(&mut buf[..max])?;
+        self.limit -= amt as u64;
+        Ok(amt)
+    }
+}
+
+impl<T: BufRead> BufRead for Take<T> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.limit == 0 {
+            return Ok(&[]);
+        }
+
+        let buf = self.inner.fill_buf()?;
+        let amt = cmp::min(buf.len() as u64, self.limit) as usize;
+        Ok(&buf[..amt])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = cmp::min(amt as u64, self.limit) as usize;
+        self.limit -= amt as u64;
+        self.inner.consume(amt);
+    }
+}
+
+/// Reads from the first reader until it is exhausted, and then from the second,
+/// mirroring `std::io::Chain`.
+#[derive(Debug)]
+pub struct Chain<T, U> {
+    first: T,
+    second: U,
+    done_first: bool,
+}
+
+impl<T, U> Chain<T, U> {
+    /// Returns the underlying readers.
+    pub fn into_inner(self) -> (T, U) {
+        (self.first, self.second)
+    }
+
+    pub fn get_ref(&self) -> (&T, &U) {
+        (&self.first, &self.second)
+    }
+
+    pub fn get_mut(&mut self) -> (&mut T, &mut U) {
+        (&mut self.first, &mut self.second)
+    }
+}
+
+impl<T: Read, U: Read> Read for Chain<T, U> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if !self.done_first {
+            match self.first.read(buf)? {
+                0 if !buf.is_empty() => self.done_first = true,
+                n => return Ok(n),
+            }
+        }
+        self.second.read(buf)
+    }
+}
+
+impl<T: BufRead, U: BufRead> BufRead for Chain<T, U> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if !self.done_first {
+            match self.first.fill_buf()? {
+                [] => self.done_first = true,
+                buf => return Ok(buf),
+            }
+        }
+        self.second.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if !self.done_first {
+            self.first.consume(amt)
+        } else {
+            self.second.consume(amt)
+        }
+    }
+}
+
 impl<W: Write + ?Sized> Write for &mut W {
     #[inline]
     fn write_all(&mut self, data: &[u8]) -> Result<()> {
@@ -58,7 +405,11 @@ impl Write for &mut [u8] {
         a.copy_from_slice(&data[..amt]);
         *self = b;
 
-        if amt == data.len() { Ok(()) } else { Err(Error) }
+        if amt == data.len() {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::WriteZero, "failed to write whole buffer"))
+        }
     }
 }
 
@@ -69,3 +420,66 @@ impl Write for Vec<u8> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::format;
+
+    #[test]
+    fn test_error_display() {
+        let error = Error::from(ErrorKind::InvalidData);
+        assert_eq!(format!("{}", error), "invalid data");
+        assert_eq!(error.message(), "invalid data");
+
+        let error = Error::new(ErrorKind::InvalidData, "bad flag");
+        assert_eq!(format!("{}", error), "invalid data: bad flag");
+        assert_eq!(error.message(), "bad flag");
+    }
+
+    #[test]
+    fn test_slice_read() {
+        let mut reader = &[1u8, 2, 3][..];
+        let mut buf = [0u8; 2];
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        assert_eq!(reader.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf, [3, 2]);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+        assert_eq!(
+            reader.read_exact(&mut buf).unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut cursor = Cursor::new(vec![1u8, 2, 3, 4, 5]);
+        let mut buf = [0u8; 2];
+        cursor.read_exact(&mut buf).unwrap();
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.fill_buf().unwrap(), &[3, 4, 5]);
+        cursor.consume(1);
+
+        let mut rest = Vec::new();
+        assert_eq!(cursor.read_to_end(&mut rest).unwrap(), 2);
+        assert_eq!(rest, [4, 5]);
+
+        cursor.set_position(10);
+        assert_eq!(cursor.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_take_and_chain() {
+        let mut reader = (&[1u8, 2, 3][..]).chain(&[4u8, 5][..]).take(4);
+        let mut bytes = Vec::new();
+        assert_eq!(reader.read_to_end(&mut bytes).unwrap(), 4);
+        assert_eq!(bytes, [1, 2, 3, 4]);
+        assert_eq!(reader.limit(), 0);
+
+        let mut reader = (&[1u8][..]).chain(&[2u8, 3][..]);
+        let mut buf = [0u8; 3];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3]);
+        assert_eq!(reader.fill_buf().unwrap(), &[0u8; 0]);
+    }
+}
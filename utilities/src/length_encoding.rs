@@ -0,0 +1,195 @@
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    error,
+    io::{Read, Result as IoResult, Write},
+    variable_length_integer::{read_variable_length_integer, variable_length_integer_size, VarInt},
+};
+
+use core::convert::TryFrom;
+
+/// An encoding for the length prefix of a collection.
+pub trait LengthEncoding {
+    /// Writes `len` into `writer`.
+    fn write_length<W: Write>(len: usize, writer: W) -> IoResult<()>;
+
+    /// Reads a length from `reader`. The length is untrusted, and must not be
+    /// used to preallocate without a limit.
+    fn read_length<R: Read>(reader: R) -> IoResult<usize>;
+
+    /// Returns the number of bytes written by `write_length` for `len`.
+    fn length_size(len: usize) -> usize;
+}
+
+/// The Bitcoin-style compact size, as in `variable_length_integer`.
+/// This is the default length encoding of all collections.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompactSize;
+
+impl LengthEncoding for CompactSize {
+    #[inline]
+    fn write_length<W: Write>(len: usize, writer: W) -> IoResult<()> {
+        VarInt(len as u64).write(writer)
+    }
+
+    #[inline]
+    fn read_length<R: Read>(reader: R) -> IoResult<usize> {
+        read_variable_length_integer(reader)
+    }
+
+    #[inline]
+    fn length_size(len: usize) -> usize {
+        variable_length_integer_size(len as u64)
+    }
+}
+
+/// Unsigned LEB128, as used by protobuf varints.
+/// https://en.wikipedia.org/wiki/LEB128#Unsigned_LEB128
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Leb128;
+
+impl LengthEncoding for Leb128 {
+    #[inline]
+    fn write_length<W: Write>(len: usize, mut writer: W) -> IoResult<()> {
+        let mut value = len as u64;
+        while value >= 0x80 {
+            (value as u8 | 0x80).write(&mut writer)?;
+            value >>= 7;
+        }
+        (value as u8).write(&mut writer)
+    }
+
+    fn read_length<R: Read>(mut reader: R) -> IoResult<usize> {
+        let mut value = 0u64;
+        // A u64 has at most 10 groups of 7 bits.
+        for i in 0..10 {
+            let byte = u8::read(&mut reader)?;
+            let group = u64::from(byte & 0x7f);
+            if i == 9 && group > 1 {
+                return Err(error("Invalid LEB128 integer: overflow"));
+            }
+            value |= group << (7 * i);
+
+            if byte & 0x80 == 0 {
+                // Reject non-canonical encodings, which end in a zero group.
+                if i > 0 && group == 0 {
+                    return Err(error("Invalid LEB128 integer: non-canonical encoding"));
+                }
+                return usize::try_from(value).map_err(|_| error("LEB128 integer exceeds usize"));
+            }
+        }
+        Err(error("Invalid LEB128 integer: overflow"))
+    }
+
+    #[inline]
+    fn length_size(len: usize) -> usize {
+        let bits = 64 - (len as u64).leading_zeros() as usize;
+        core::cmp::max(1, bits.div_ceil(7))
+    }
+}
+
+/// A little-endian `u32`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixedU32;
+
+impl LengthEncoding for FixedU32 {
+    #[inline]
+    fn write_length<W: Write>(len: usize, writer: W) -> IoResult<()> {
+        u32::try_from(len)
+            .map_err(|_| error("Length exceeds u32"))?
+            .write(writer)
+    }
+
+    #[inline]
+    fn read_length<R: Read>(reader: R) -> IoResult<usize> {
+        usize::try_from(u32::read(reader)?).map_err(|_| error("Length exceeds usize"))
+    }
+
+    #[inline]
+    fn length_size(_len: usize) -> usize {
+        4
+    }
+}
+
+/// A little-endian `u64`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixedU64;
+
+impl LengthEncoding for FixedU64 {
+    #[inline]
+    fn write_length<W: Write>(len: usize, writer: W) -> IoResult<()> {
+        (len as u64).write(writer)
+    }
+
+    #[inline]
+    fn read_length<R: Read>(reader: R) -> IoResult<usize> {
+        usize::try_from(u64::read(reader)?).map_err(|_| error("Length exceeds usize"))
+    }
+
+    #[inline]
+    fn length_size(_len: usize) -> usize {
+        8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{variable_length_integer::variable_length_integer, Vec};
+
+    const LENGTHS: [usize; 9] = [0, 1, 127, 128, 252, 253, 16_383, 16_384, u32::MAX as usize];
+
+    fn roundtrip<L: LengthEncoding>() {
+        for len in LENGTHS.iter() {
+            let mut bytes = Vec::new();
+            L::write_length(*len, &mut bytes).unwrap();
+            assert_eq!(bytes.len(), L::length_size(*len));
+            assert_eq!(L::read_length(&bytes[..]).unwrap(), *len);
+            assert!(L::read_length(&bytes[..bytes.len() - 1]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        roundtrip::<CompactSize>();
+        roundtrip::<Leb128>();
+        roundtrip::<FixedU32>();
+        roundtrip::<FixedU64>();
+    }
+
+    #[test]
+    fn test_compact_size_matches_variable_length_integer() {
+        for len in LENGTHS.iter() {
+            let mut bytes = Vec::new();
+            CompactSize::write_length(*len, &mut bytes).unwrap();
+            assert_eq!(bytes, variable_length_integer(*len as u64));
+        }
+    }
+
+    #[test]
+    fn test_leb128() {
+        let vectors: [(usize, &[u8]); 5] = [
+            (0, &[0x00]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (300, &[0xac, 0x02]),
+            (624_485, &[0xe5, 0x8e, 0x26]),
+        ];
+        for (len, expected) in vectors.iter() {
+            let mut bytes = Vec::new();
+            Leb128::write_length(*len, &mut bytes).unwrap();
+            assert_eq!(&bytes[..], *expected);
+        }
+
+        // Non-canonical and overflowing encodings are rejected.
+        assert!(Leb128::read_length(&[0x80, 0x00][..]).is_err());
+        assert!(Leb128::read_length(&[0xff; 10][..]).is_err());
+        assert!(Leb128::read_length(&[0x80; 11][..]).is_err());
+    }
+
+    #[test]
+    fn test_fixed_u32_rejects_large_length() {
+        if let Some(len) = (u32::MAX as usize).checked_add(1) {
+            assert!(FixedU32::write_length(len, Vec::new()).is_err());
+        }
+    }
+}
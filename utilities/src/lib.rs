@@ -1,5 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 #[cfg(all(test, not(feature = "std")))]
 #[macro_use]
 extern crate std;
@@ -7,18 +10,48 @@ extern crate std;
 #[cfg(not(feature = "std"))]
 #[allow(unused_imports)]
 #[doc(hidden)]
-pub use alloc::{boxed::Box, format, vec, vec::Vec};
+pub use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    vec,
+    vec::Vec,
+};
 
 #[cfg(feature = "std")]
 #[allow(unused_imports)]
 #[doc(hidden)]
-pub use std::{boxed::Box, format, vec, vec::Vec};
+pub use std::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    format,
+    vec,
+    vec::Vec,
+};
 
+#[cfg(feature = "tokio")]
+pub mod async_bytes;
 pub mod biginteger;
 pub mod bititerator;
+pub mod bits;
 #[macro_use]
 pub mod bytes;
+pub mod bytes_ref;
+#[cfg(feature = "std")]
+pub mod encoding;
+#[cfg(feature = "std")]
+pub mod envelope;
+pub mod fiat_shamir;
+pub mod length_encoding;
+pub mod limited_reader;
 pub mod rand;
+#[cfg(all(feature = "serde", feature = "std"))]
+pub mod serde_bytes;
+pub mod serialize;
+pub mod sinks;
+pub mod streaming;
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+pub mod testing;
 pub mod variable_length_integer;
 
 #[cfg(not(feature = "std"))]
@@ -27,14 +60,9 @@ pub mod io;
 #[cfg(feature = "std")]
 pub use std::io;
 
-#[cfg(not(feature = "std"))]
-fn error(_msg: &'static str) -> io::Error {
-    io::Error
-}
-
-#[cfg(feature = "std")]
+/// Returns an `InvalidData` error with the given message, with or without `std`.
 fn error(msg: &'static str) -> io::Error {
-    io::Error::new(io::ErrorKind::Other, msg)
+    io::Error::new(io::ErrorKind::InvalidData, msg)
 }
 
 #[macro_export]
@@ -0,0 +1,147 @@
+use crate::{
+    error,
+    io::{Read, Result as IoResult},
+};
+
+/// The budget available for decoding untrusted input.
+///
+/// `max_bytes` bounds the number of bytes read from the underlying reader, and
+/// `max_elements` bounds the total number of elements that length-prefixed
+/// `FromBytes` impls may allocate for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeContext {
+    max_bytes: usize,
+    max_elements: usize,
+}
+
+impl DecodeContext {
+    pub fn new(max_bytes: usize, max_elements: usize) -> Self {
+        Self {
+            max_bytes,
+            max_elements,
+        }
+    }
+
+    /// Returns the number of bytes that may still be read.
+    pub fn remaining_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Returns the number of elements that may still be allocated.
+    pub fn remaining_elements(&self) -> usize {
+        self.max_elements
+    }
+
+    /// Deducts `num_bytes` from the byte budget, or errors if it is exceeded.
+    pub fn consume_bytes(&mut self, num_bytes: usize) -> IoResult<()> {
+        match self.max_bytes.checked_sub(num_bytes) {
+            Some(remaining) => {
+                self.max_bytes = remaining;
+                Ok(())
+            }
+            None => Err(error("DecodeContext: byte limit exceeded")),
+        }
+    }
+
+    /// Deducts `num_elements` from the element budget, or errors if it is exceeded.
+    /// Must be called with an untrusted length before allocating for it.
+    pub fn reserve_elements(&mut self, num_elements: usize) -> IoResult<()> {
+        match self.max_elements.checked_sub(num_elements) {
+            Some(remaining) => {
+                self.max_elements = remaining;
+                Ok(())
+            }
+            None => Err(error("DecodeContext: element limit exceeded")),
+        }
+    }
+}
+
+/// A reader that enforces a `DecodeContext` over the underlying reader.
+#[derive(Debug)]
+pub struct LimitedReader<R> {
+    inner: R,
+    context: DecodeContext,
+}
+
+impl<R: Read> LimitedReader<R> {
+    pub fn new(inner: R, context: DecodeContext) -> Self {
+        Self { inner, context }
+    }
+
+    /// Returns the remaining budget.
+    pub fn context(&self) -> &DecodeContext {
+        &self.context
+    }
+
+    /// Deducts `num_elements` from the element budget, or errors if it is exceeded.
+    pub fn reserve_elements(&mut self, num_elements: usize) -> IoResult<()> {
+        self.context.reserve_elements(num_elements)
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.context.remaining_bytes() == 0 {
+            return Err(error("DecodeContext: byte limit exceeded"));
+        }
+
+        let max = core::cmp::min(buf.len(), self.context.remaining_bytes());
+        let num_bytes = self.inner.read(&mut buf[..max])?;
+        self.context.consume_bytes(num_bytes)?;
+        Ok(num_bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        bytes::{FromBytes, ToBytes},
+        variable_length_integer::variable_length_integer,
+        BTreeSet,
+    };
+
+    #[test]
+    fn test_byte_limit() {
+        let bytes = [7u8; 16];
+
+        let mut reader = LimitedReader::new(&bytes[..], DecodeContext::new(8, 0));
+        assert_eq!(u64::read_limited(&mut reader).unwrap(), 0x0707_0707_0707_0707);
+        assert_eq!(reader.context().remaining_bytes(), 0);
+        assert!(u8::read_limited(&mut reader).is_err());
+
+        let mut reader = LimitedReader::new(&bytes[..], DecodeContext::new(6, 0));
+        assert!(u64::read_limited(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_element_limit() {
+        let set: BTreeSet<u8> = (0..10u8).collect();
+        let bytes = to_bytes![set].unwrap();
+
+        let mut reader = LimitedReader::new(&bytes[..], DecodeContext::new(bytes.len(), 10));
+        assert_eq!(BTreeSet::<u8>::read_limited(&mut reader).unwrap(), set);
+        assert_eq!(reader.context().remaining_elements(), 0);
+
+        let mut reader = LimitedReader::new(&bytes[..], DecodeContext::new(bytes.len(), 9));
+        assert!(BTreeSet::<u8>::read_limited(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_malicious_length_prefix() {
+        // Claims u64::MAX elements, but carries none of them.
+        let bytes = variable_length_integer(u64::MAX);
+
+        let mut reader = LimitedReader::new(&bytes[..], DecodeContext::new(1 << 20, 1 << 16));
+        assert!(BTreeSet::<u64>::read_limited(&mut reader).is_err());
+        assert_eq!(reader.context().remaining_elements(), 1 << 16);
+    }
+}
@@ -1,8 +1,17 @@
+use crate::Vec;
+
+use core::ops::Range;
 use rand::{
-    distributions::{Distribution, Standard},
+    distributions::{uniform::SampleUniform, Distribution, Standard},
     Rng,
+    SeedableRng,
 };
+use rand_chacha::ChaChaRng;
 
+/// Samples a uniformly random value. This is implemented for every type that `Standard`
+/// can sample, which includes tuples, arrays of up to 32 elements and `Option<T>` of
+/// such types. New types should implement `Distribution<T>` for `Standard` to be
+/// usable in these composites.
 pub trait UniformRand: Sized {
     fn rand<R: Rng + ?Sized>(rng: &mut R) -> Self;
 }
@@ -17,13 +26,226 @@ where
     }
 }
 
+/// Returns a vector of `len` uniformly random values.
+pub fn rand_vec<T: UniformRand, R: Rng + ?Sized>(len: usize, rng: &mut R) -> Vec<T> {
+    (0..len).map(|_| T::rand(rng)).collect()
+}
+
+/// Samples a value uniformly from a bounded range.
+pub trait UniformRandRange: Sized {
+    /// Returns a uniformly random value in `range`.
+    /// Panics if `range` is empty.
+    fn rand_range<R: Rng + ?Sized>(range: Range<Self>, rng: &mut R) -> Self;
+}
+
+impl<T: SampleUniform + PartialOrd> UniformRandRange for T {
+    #[inline]
+    fn rand_range<R: Rng + ?Sized>(range: Range<Self>, rng: &mut R) -> Self {
+        assert!(
+            range.start < range.end,
+            "UniformRandRange::rand_range called with an empty range"
+        );
+        rng.gen_range(range.start, range.end)
+    }
+}
+
+/// Forks `rng` into `num_children` independent child RNGs, for use by parallel tasks.
+/// A single seed is drawn from `rng`, and child `i` is the ChaCha20 stream `i` under that
+/// seed, so the children depend only on the state of `rng` and never on thread scheduling.
+/// `rng` is advanced by the same amount regardless of `num_children`.
+pub fn fork_rng<R: Rng + ?Sized>(rng: &mut R, num_children: usize) -> Vec<ChaChaRng> {
+    let seed: [u8; 32] = rng.gen();
+    (0..num_children as u64)
+        .map(|i| {
+            let mut child = ChaChaRng::from_seed(seed);
+            child.set_stream(i);
+            child
+        })
+        .collect()
+}
+
 /// Should be used only for tests, not for any real world usage.
 pub fn test_rng() -> rand::rngs::StdRng {
-    use rand::SeedableRng;
-
     // arbitrary seed
     let seed = [
         1, 0, 0, 0, 23, 0, 0, 0, 200, 1, 0, 0, 210, 30, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
     rand::rngs::StdRng::from_seed(seed)
 }
+
+/// The environment variable from which `test_seed` reads its seed.
+#[cfg(feature = "std")]
+pub const TEST_SEED_ENV_VAR: &str = "SNARKVM_TEST_SEED";
+
+/// Parses a test seed, given in decimal or as `0x`-prefixed hexadecimal.
+#[cfg(feature = "std")]
+fn parse_test_seed(seed: &str) -> Option<u64> {
+    let seed = seed.trim();
+    match seed.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => seed.parse().ok(),
+    }
+}
+
+/// Returns the seed of this test process. The seed is read from `TEST_SEED_ENV_VAR` if set,
+/// and otherwise generated randomly, and is the same for every call in the process.
+#[cfg(feature = "std")]
+pub fn test_seed() -> u64 {
+    static SEED: std::sync::OnceLock<u64> = std::sync::OnceLock::new();
+
+    *SEED.get_or_init(|| match std::env::var(TEST_SEED_ENV_VAR) {
+        Ok(value) => {
+            parse_test_seed(&value).unwrap_or_else(|| panic!("{} is not a valid seed: {:?}", TEST_SEED_ENV_VAR, value))
+        }
+        Err(_) => rand::thread_rng().gen(),
+    })
+}
+
+/// Returns the RNG for the stream identified by `name`, under the given seed.
+#[cfg(feature = "std")]
+fn seeded_test_rng(seed: u64, name: &str) -> rand::rngs::StdRng {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_le_bytes());
+    hasher.update(name.as_bytes());
+
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&hasher.finalize());
+    rand::rngs::StdRng::from_seed(seed)
+}
+
+/// Returns a test RNG seeded by `test_seed`, for a stream identified by `name`.
+/// Distinct names yield independent streams, so each test can reproduce its own
+/// randomness regardless of which other tests run. Should be used only for tests.
+///
+/// The seed and the name are printed to stderr on every call, so that the output
+/// of a failing test shows how to rerun it with the same randomness.
+#[cfg(feature = "std")]
+pub fn named_test_rng(name: &str) -> rand::rngs::StdRng {
+    let seed = test_seed();
+    eprintln!("Using test seed {}={} for stream {:?}", TEST_SEED_ENV_VAR, seed, name);
+    seeded_test_rng(seed, name)
+}
+
+/// Returns a test RNG seeded by `test_seed`. Should be used only for tests.
+#[cfg(feature = "std")]
+pub fn reproducible_test_rng() -> rand::rngs::StdRng {
+    named_test_rng("")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_composites() {
+        // Tuples and arrays draw their elements in order, as the elements themselves would be drawn.
+        let (a, b): (u8, [u64; 4]) = UniformRand::rand(&mut test_rng());
+        let mut rng = test_rng();
+        assert_eq!(a, u8::rand(&mut rng));
+        assert_eq!(b, <[u64; 4]>::rand(&mut rng));
+
+        let array = <[u32; 32]>::rand(&mut test_rng());
+        assert_eq!(array.to_vec(), rand_vec::<u32, _>(32, &mut test_rng()));
+        for (i, value) in array.iter().enumerate() {
+            assert!(!array[..i].contains(value));
+        }
+
+        // Both variants of an option are drawn, as are both values inside it.
+        let mut rng = test_rng();
+        let options: Vec<Option<bool>> = rand_vec(1000, &mut rng);
+        for expected in [None, Some(false), Some(true)].iter() {
+            let count = options.iter().filter(|option| *option == expected).count();
+            assert!(count > 100, "{:?} was drawn {} times", expected, count);
+        }
+
+        assert_eq!(rand_vec::<[u32; 2], _>(100, &mut rng).len(), 100);
+        assert!(rand_vec::<u64, _>(0, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_rand_range() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let value = u16::rand_range(10..13, &mut rng);
+            assert!((10..13).contains(&value));
+        }
+        assert_eq!(i64::rand_range(-5..-4, &mut rng), -5);
+    }
+
+    #[test]
+    fn test_fork_rng() {
+        let children = fork_rng(&mut test_rng(), 4);
+        let outputs: Vec<[u64; 4]> = children.into_iter().map(|mut child| child.gen()).collect();
+        for (i, output) in outputs.iter().enumerate() {
+            assert!(outputs[..i].iter().all(|previous| previous != output));
+        }
+
+        // Forking is deterministic, and a child does not depend on the number of siblings.
+        let mut rng = test_rng();
+        let mut first = fork_rng(&mut rng, 1).remove(0);
+        assert_eq!(first.gen::<[u64; 4]>(), outputs[0]);
+
+        // The parent is advanced by the same amount regardless of the number of children.
+        let mut other = test_rng();
+        fork_rng(&mut other, 100);
+        assert_eq!(rng.gen::<u64>(), other.gen::<u64>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rand_range_empty() {
+        u8::rand_range(3..3, &mut test_rng());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_parse_test_seed() {
+        assert_eq!(parse_test_seed("1234"), Some(1234));
+        assert_eq!(parse_test_seed(" 0xff\n"), Some(255));
+        assert_eq!(parse_test_seed(&u64::MAX.to_string()), Some(u64::MAX));
+        assert_eq!(parse_test_seed("seed"), None);
+        assert_eq!(parse_test_seed("-1"), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_named_test_rng() {
+        let a: [u64; 4] = named_test_rng("a").gen();
+        assert_eq!(a, named_test_rng("a").gen::<[u64; 4]>());
+        assert_ne!(a, named_test_rng("b").gen::<[u64; 4]>());
+        assert_eq!(test_seed(), test_seed());
+    }
+
+    /// Prints a draw from a named test RNG, for `test_seed_from_env_var`.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore]
+    fn print_named_test_rng() {
+        println!("draw={}", named_test_rng("child").gen::<u64>());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_seed_from_env_var() {
+        // The seed is cached for the whole process, so it is set in a child process.
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "rand::test::print_named_test_rng",
+                "--exact",
+                "--ignored",
+                "--nocapture",
+            ])
+            .env(TEST_SEED_ENV_VAR, "0x1234")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let expected = seeded_test_rng(0x1234, "child").gen::<u64>();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(&format!("draw={}", expected)));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Using test seed SNARKVM_TEST_SEED=4660 for stream \"child\""));
+    }
+}
@@ -0,0 +1,161 @@
+//! Serde adapters for `ToBytes` and `FromBytes` types, for use with `#[serde(with = "...")]`.
+//!
+//! Values are serialized as a hex string in human-readable formats,
+//! and as raw bytes in binary formats.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Message {
+//!     #[serde(with = "snarkvm_utilities::serde_bytes")]
+//!     proof: Proof,
+//!     #[serde(with = "snarkvm_utilities::serde_bytes::option")]
+//!     commitment: Option<Commitment>,
+//! }
+//! ```
+
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    encoding::{from_hex, to_hex},
+    Vec,
+};
+
+use core::{fmt, marker::PhantomData};
+use serde::{
+    de::{self, Deserializer, SeqAccess, Visitor},
+    ser::{self, Serializer},
+    Deserialize,
+};
+
+pub fn serialize<T: ToBytes, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&to_hex(value).map_err(ser::Error::custom)?)
+    } else {
+        serializer.serialize_bytes(&value.to_bytes_vec().map_err(ser::Error::custom)?)
+    }
+}
+
+pub fn deserialize<'de, T: FromBytes, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        let string = String::deserialize(deserializer)?;
+        from_hex(&string).map_err(de::Error::custom)
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor(PhantomData))
+    }
+}
+
+struct BytesVisitor<T>(PhantomData<T>);
+
+impl<'de, T: FromBytes> Visitor<'de> for BytesVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<T, E> {
+        T::from_bytes_exact(bytes).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        // The size hint is untrusted, so the bytes are not preallocated.
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+/// Adapters for `Option<T>` fields, where `T` is a `ToBytes` and `FromBytes` type.
+pub mod option {
+    use super::*;
+
+    use serde::Serialize;
+
+    /// Borrows a value so that it serializes through the parent adapter.
+    struct Wrapper<'a, T>(&'a T);
+
+    impl<'a, T: ToBytes> Serialize for Wrapper<'a, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    /// Owns a value that deserializes through the parent adapter.
+    struct Owned<T>(T);
+
+    impl<'de, T: FromBytes> Deserialize<'de> for Owned<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            super::deserialize(deserializer).map(Owned)
+        }
+    }
+
+    pub fn serialize<T: ToBytes, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.as_ref().map(Wrapper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: FromBytes, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+        Ok(Option::<Owned<T>>::deserialize(deserializer)?.map(|owned| owned.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytes::ToBytes, BTreeSet};
+
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Message {
+        #[serde(with = "crate::serde_bytes")]
+        proof: [u64; 2],
+        #[serde(with = "crate::serde_bytes")]
+        inputs: BTreeSet<u16>,
+        #[serde(with = "crate::serde_bytes::option")]
+        commitment: Option<[u8; 4]>,
+    }
+
+    fn message() -> Message {
+        Message {
+            proof: [1, 0xff],
+            inputs: [3u16, 1].iter().copied().collect(),
+            commitment: Some([0xaa, 0xbb, 0xcc, 0xdd]),
+        }
+    }
+
+    #[test]
+    fn test_human_readable_is_hex() {
+        let json = serde_json::to_string(&message()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"proof":"0100000000000000ff00000000000000","inputs":"0201000300","commitment":"aabbccdd"}"#
+        );
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message());
+
+        let none = Message {
+            commitment: None,
+            ..message()
+        };
+        let json = serde_json::to_string(&none).unwrap();
+        assert!(json.ends_with(r#""commitment":null}"#));
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), none);
+    }
+
+    #[test]
+    fn test_binary_is_raw_bytes() {
+        let bytes = bincode::serialize(&message()).unwrap();
+        // A u64 length prefix, then the raw 16 bytes of the proof.
+        assert_eq!(&bytes[..8], &16u64.to_le_bytes());
+        assert_eq!(&bytes[8..24], &to_bytes![message().proof].unwrap()[..]);
+        assert_eq!(bincode::deserialize::<Message>(&bytes).unwrap(), message());
+    }
+
+    #[test]
+    fn test_rejects_invalid_input() {
+        let trailing = r#"{"proof":"0100000000000000ff0000000000000000","inputs":"00","commitment":null}"#;
+        assert!(serde_json::from_str::<Message>(trailing).is_err());
+
+        let unsorted = r#"{"proof":"0100000000000000ff00000000000000","inputs":"0203000100","commitment":null}"#;
+        assert!(serde_json::from_str::<Message>(unsorted).is_err());
+    }
+}
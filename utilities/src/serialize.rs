@@ -0,0 +1,353 @@
+use crate::{
+    bytes::LengthPrefixed,
+    io::{Read, Result as IoResult, Write},
+    length_encoding::{CompactSize, LengthEncoding},
+    Vec,
+};
+
+/// Whether to use the compressed form of a serialization, where one exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compress {
+    Yes,
+    No,
+}
+
+/// Whether to check that deserialized values are well-formed, e.g. that a group
+/// element is on the curve and in the prime order subgroup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Validate {
+    Yes,
+    No,
+}
+
+/// Checks that a deserialized value is well-formed.
+pub trait Valid: Sized {
+    fn check(&self) -> IoResult<()>;
+
+    fn batch_check<'a>(batch: impl Iterator<Item = &'a Self>) -> IoResult<()>
+    where
+        Self: 'a,
+    {
+        for item in batch {
+            item.check()?;
+        }
+        Ok(())
+    }
+}
+
+pub trait CanonicalSerialize {
+    /// Serializes `self` into `writer`, in the given form.
+    fn serialize_with_mode<W: Write>(&self, writer: W, compress: Compress) -> IoResult<()>;
+
+    /// Returns the number of bytes written by `serialize_with_mode` in the given form.
+    fn serialized_size_with_mode(&self, compress: Compress) -> usize;
+
+    #[inline]
+    fn serialize_compressed<W: Write>(&self, writer: W) -> IoResult<()> {
+        self.serialize_with_mode(writer, Compress::Yes)
+    }
+
+    #[inline]
+    fn serialize_uncompressed<W: Write>(&self, writer: W) -> IoResult<()> {
+        self.serialize_with_mode(writer, Compress::No)
+    }
+}
+
+pub trait CanonicalDeserialize: Valid {
+    /// Reads `Self` from `reader` in the given form, checking it if `validate` is set.
+    fn deserialize_with_mode<R: Read>(reader: R, compress: Compress, validate: Validate) -> IoResult<Self>;
+
+    #[inline]
+    fn deserialize_compressed<R: Read>(reader: R) -> IoResult<Self> {
+        Self::deserialize_with_mode(reader, Compress::Yes, Validate::Yes)
+    }
+
+    #[inline]
+    fn deserialize_compressed_unchecked<R: Read>(reader: R) -> IoResult<Self> {
+        Self::deserialize_with_mode(reader, Compress::Yes, Validate::No)
+    }
+
+    #[inline]
+    fn deserialize_uncompressed<R: Read>(reader: R) -> IoResult<Self> {
+        Self::deserialize_with_mode(reader, Compress::No, Validate::Yes)
+    }
+
+    #[inline]
+    fn deserialize_uncompressed_unchecked<R: Read>(reader: R) -> IoResult<Self> {
+        Self::deserialize_with_mode(reader, Compress::No, Validate::No)
+    }
+}
+
+/// Implements the canonical traits for a type whose `ToBytes` encoding is
+/// already canonical, and for which every decoded value is valid. Both forms
+/// are the `ToBytes` encoding.
+#[macro_export]
+macro_rules! impl_canonical_via_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl $crate::serialize::Valid for $ty {
+                #[inline]
+                fn check(&self) -> $crate::io::Result<()> {
+                    Ok(())
+                }
+            }
+
+            impl $crate::serialize::CanonicalSerialize for $ty {
+                #[inline]
+                fn serialize_with_mode<W: $crate::io::Write>(
+                    &self,
+                    writer: W,
+                    _compress: $crate::serialize::Compress,
+                ) -> $crate::io::Result<()> {
+                    $crate::bytes::ToBytes::write(self, writer)
+                }
+
+                #[inline]
+                fn serialized_size_with_mode(&self, _compress: $crate::serialize::Compress) -> usize {
+                    $crate::bytes::SerializedSize::serialized_size(self)
+                }
+            }
+
+            impl $crate::serialize::CanonicalDeserialize for $ty {
+                #[inline]
+                fn deserialize_with_mode<R: $crate::io::Read>(
+                    reader: R,
+                    _compress: $crate::serialize::Compress,
+                    _validate: $crate::serialize::Validate,
+                ) -> $crate::io::Result<Self> {
+                    $crate::bytes::FromBytes::read(reader)
+                }
+            }
+        )*
+    };
+}
+
+impl_canonical_via_bytes!(u8, u16, u32, u64, i64, bool, ());
+
+macro_rules! array_canonical {
+    ($($N:expr),*) => {
+        $(impl_canonical_via_bytes!([u8; $N], [u16; $N], [u32; $N], [u64; $N]);)*
+    };
+}
+
+array_canonical!(
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30,
+    31, 32
+);
+
+impl<T: Valid> Valid for Vec<T> {
+    #[inline]
+    fn check(&self) -> IoResult<()> {
+        T::batch_check(self.iter())
+    }
+}
+
+/// Writes a count in the length encoding `L`, followed by each item in the given form.
+fn serialize_items<L: LengthEncoding, T: CanonicalSerialize, W: Write>(
+    items: &[T],
+    mut writer: W,
+    compress: Compress,
+) -> IoResult<()> {
+    L::write_length(items.len(), &mut writer)?;
+    for item in items {
+        item.serialize_with_mode(&mut writer, compress)?;
+    }
+    Ok(())
+}
+
+#[inline]
+fn serialized_items_size<L: LengthEncoding, T: CanonicalSerialize>(items: &[T], compress: Compress) -> usize {
+    L::length_size(items.len())
+        + items
+            .iter()
+            .map(|item| item.serialized_size_with_mode(compress))
+            .sum::<usize>()
+}
+
+/// Reads a count in the length encoding `L`, followed by that many items in the given form.
+fn deserialize_items<L: LengthEncoding, T: CanonicalDeserialize, R: Read>(
+    mut reader: R,
+    compress: Compress,
+    validate: Validate,
+) -> IoResult<Vec<T>> {
+    let len = L::read_length(&mut reader)?;
+
+    // The length is untrusted, so the elements are not preallocated.
+    let mut items = Vec::new();
+    for _ in 0..len {
+        // Elements are checked once as a batch below, rather than individually.
+        items.push(T::deserialize_with_mode(&mut reader, compress, Validate::No)?);
+    }
+    if validate == Validate::Yes {
+        items.check()?;
+    }
+    Ok(items)
+}
+
+/// Serialized as a `CompactSize` count followed by each element in the given form.
+impl<T: CanonicalSerialize> CanonicalSerialize for Vec<T> {
+    #[inline]
+    fn serialize_with_mode<W: Write>(&self, writer: W, compress: Compress) -> IoResult<()> {
+        serialize_items::<CompactSize, _, _>(self, writer, compress)
+    }
+
+    #[inline]
+    fn serialized_size_with_mode(&self, compress: Compress) -> usize {
+        serialized_items_size::<CompactSize, _>(self, compress)
+    }
+}
+
+impl<T: CanonicalDeserialize> CanonicalDeserialize for Vec<T> {
+    #[inline]
+    fn deserialize_with_mode<R: Read>(reader: R, compress: Compress, validate: Validate) -> IoResult<Self> {
+        deserialize_items::<CompactSize, _, _>(reader, compress, validate)
+    }
+}
+
+impl<T: Valid, L: LengthEncoding> Valid for LengthPrefixed<Vec<T>, L> {
+    #[inline]
+    fn check(&self) -> IoResult<()> {
+        self.inner.check()
+    }
+}
+
+/// Serialized as a count in the length encoding `L` followed by each element in the given form.
+impl<T: CanonicalSerialize, L: LengthEncoding> CanonicalSerialize for LengthPrefixed<Vec<T>, L> {
+    #[inline]
+    fn serialize_with_mode<W: Write>(&self, writer: W, compress: Compress) -> IoResult<()> {
+        serialize_items::<L, _, _>(&self.inner, writer, compress)
+    }
+
+    #[inline]
+    fn serialized_size_with_mode(&self, compress: Compress) -> usize {
+        serialized_items_size::<L, _>(&self.inner, compress)
+    }
+}
+
+impl<T: CanonicalDeserialize, L: LengthEncoding> CanonicalDeserialize for LengthPrefixed<Vec<T>, L> {
+    #[inline]
+    fn deserialize_with_mode<R: Read>(reader: R, compress: Compress, validate: Validate) -> IoResult<Self> {
+        Ok(Self::new(deserialize_items::<L, _, _>(reader, compress, validate)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        bytes::{FromBytes, ToBytes},
+        error,
+        Vec,
+    };
+
+    /// A stand-in for a group element, with a one byte compressed form.
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    impl Valid for Point {
+        fn check(&self) -> IoResult<()> {
+            match self.y == self.x.wrapping_mul(3) {
+                true => Ok(()),
+                false => Err(error("point is not on the curve")),
+            }
+        }
+    }
+
+    impl CanonicalSerialize for Point {
+        fn serialize_with_mode<W: Write>(&self, writer: W, compress: Compress) -> IoResult<()> {
+            match compress {
+                Compress::Yes => self.x.write(writer),
+                Compress::No => [self.x, self.y].write(writer),
+            }
+        }
+
+        fn serialized_size_with_mode(&self, compress: Compress) -> usize {
+            match compress {
+                Compress::Yes => 1,
+                Compress::No => 2,
+            }
+        }
+    }
+
+    impl CanonicalDeserialize for Point {
+        fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> IoResult<Self> {
+            let point = match compress {
+                Compress::Yes => {
+                    let x = u8::read(&mut reader)?;
+                    Point {
+                        x,
+                        y: x.wrapping_mul(3),
+                    }
+                }
+                Compress::No => {
+                    let [x, y] = <[u8; 2]>::read(&mut reader)?;
+                    Point { x, y }
+                }
+            };
+            if validate == Validate::Yes {
+                point.check()?;
+            }
+            Ok(point)
+        }
+    }
+
+    #[test]
+    fn test_primitives_match_to_bytes() {
+        let value = 0x0102_0304_0506_0708u64;
+        let mut compressed = Vec::new();
+        value.serialize_compressed(&mut compressed).unwrap();
+        assert_eq!(compressed, to_bytes![value].unwrap());
+        assert_eq!(compressed.len(), value.serialized_size_with_mode(Compress::Yes));
+        assert_eq!(value, u64::deserialize_uncompressed(&compressed[..]).unwrap());
+        assert!(bool::deserialize_compressed(&[2u8][..]).is_err());
+    }
+
+    #[test]
+    fn test_vec_modes() {
+        let points: Vec<Point> = (0..10u8).map(|x| Point { x, y: x * 3 }).collect();
+
+        for compress in [Compress::Yes, Compress::No].iter() {
+            let mut bytes = Vec::new();
+            points.serialize_with_mode(&mut bytes, *compress).unwrap();
+            assert_eq!(bytes.len(), points.serialized_size_with_mode(*compress));
+            let decoded = Vec::<Point>::deserialize_with_mode(&bytes[..], *compress, Validate::Yes).unwrap();
+            assert_eq!(decoded, points);
+        }
+    }
+
+    #[test]
+    fn test_unchecked_skips_validation() {
+        let invalid = vec![Point { x: 1, y: 2 }];
+        let mut bytes = Vec::new();
+        invalid.serialize_uncompressed(&mut bytes).unwrap();
+
+        assert!(Vec::<Point>::deserialize_uncompressed(&bytes[..]).is_err());
+        assert_eq!(
+            Vec::<Point>::deserialize_uncompressed_unchecked(&bytes[..]).unwrap(),
+            invalid
+        );
+    }
+
+    #[test]
+    fn test_length_prefixed_vec() {
+        use crate::length_encoding::FixedU32;
+
+        let points: Vec<Point> = (0..3u8).map(|x| Point { x, y: x * 3 }).collect();
+        let prefixed = LengthPrefixed::<_, FixedU32>::new(points);
+
+        let mut bytes = Vec::new();
+        prefixed.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes, [3, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(bytes.len(), prefixed.serialized_size_with_mode(Compress::Yes));
+        let decoded = LengthPrefixed::<Vec<Point>, FixedU32>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded, prefixed);
+
+        let invalid = LengthPrefixed::<_, FixedU32>::new(vec![Point { x: 1, y: 2 }]);
+        let mut bytes = Vec::new();
+        invalid.serialize_uncompressed(&mut bytes).unwrap();
+        assert!(LengthPrefixed::<Vec<Point>, FixedU32>::deserialize_uncompressed(&bytes[..]).is_err());
+    }
+}
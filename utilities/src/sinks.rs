@@ -0,0 +1,158 @@
+use crate::{
+    bytes::ToBytes,
+    io::{Result as IoResult, Write},
+};
+
+use blake2::{Blake2b, Blake2s};
+use sha2::{
+    digest::{Digest, Output},
+    Sha256,
+};
+
+/// A `Write` sink that discards its input and only counts the number of bytes written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ByteCounter {
+    count: usize,
+}
+
+impl ByteCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the number of bytes `value` serializes to, without allocating a buffer.
+    pub fn measure<T: ToBytes>(value: &T) -> IoResult<usize> {
+        let mut counter = Self::new();
+        value.write(&mut counter)?;
+        Ok(counter.count)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Write for ByteCounter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for ByteCounter {
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> IoResult<()> {
+        self.count += data.len();
+        Ok(())
+    }
+}
+
+/// A `Write` sink that feeds its input directly into the digest `D`.
+#[derive(Clone, Debug, Default)]
+pub struct DigestWriter<D: Digest> {
+    hasher: D,
+}
+
+/// A `Write` sink that computes the BLAKE2s digest of its input.
+pub type Blake2sWriter = DigestWriter<Blake2s>;
+
+/// A `Write` sink that computes the BLAKE2b digest of its input.
+pub type Blake2bWriter = DigestWriter<Blake2b>;
+
+/// A `Write` sink that computes the SHA-256 digest of its input.
+pub type Sha256Writer = DigestWriter<Sha256>;
+
+impl<D: Digest> DigestWriter<D> {
+    pub fn new() -> Self {
+        Self { hasher: D::new() }
+    }
+
+    /// Returns the digest of all bytes written so far.
+    pub fn finalize(self) -> Output<D> {
+        self.hasher.finalize()
+    }
+
+    /// Returns the digest of the serialization of `value`, without allocating a buffer.
+    pub fn digest<T: ToBytes>(value: &T) -> IoResult<Output<D>> {
+        let mut writer = Self::new();
+        value.write(&mut writer)?;
+        Ok(writer.finalize())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<D: Digest> Write for DigestWriter<D> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<D: Digest> Write for DigestWriter<D> {
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> IoResult<()> {
+        self.hasher.update(data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bytes::SerializedSize, BTreeMap, Vec};
+
+    #[test]
+    fn test_byte_counter() {
+        let mut counter = ByteCounter::new();
+        counter.write_all(&[0u8; 10]).unwrap();
+        counter.write_all(&[]).unwrap();
+        counter.write_all(&[1u8; 7]).unwrap();
+        assert_eq!(counter.count(), 17);
+    }
+
+    #[test]
+    fn test_measure_matches_serialized_size() {
+        let vector: Vec<u64> = (0..100).collect();
+        assert_eq!(ByteCounter::measure(&vector).unwrap(), vector.serialized_size());
+        assert_eq!(vector.serialized_size(), to_bytes![vector].unwrap().len());
+
+        let map: BTreeMap<u16, [u32; 3]> = (0..300u16).map(|i| (i, [u32::from(i); 3])).collect();
+        assert_eq!(ByteCounter::measure(&map).unwrap(), map.serialized_size());
+        assert_eq!(map.serialized_size(), to_bytes![map].unwrap().len());
+    }
+
+    #[test]
+    fn test_digest_writer_matches_digest() {
+        let map: BTreeMap<u16, [u32; 3]> = (0..300u16).map(|i| (i, [u32::from(i); 3])).collect();
+        let bytes = to_bytes![map].unwrap();
+
+        assert_eq!(Blake2sWriter::digest(&map).unwrap(), Blake2s::digest(&bytes));
+        assert_eq!(Blake2bWriter::digest(&map).unwrap(), Blake2b::digest(&bytes));
+        assert_eq!(Sha256Writer::digest(&map).unwrap(), Sha256::digest(&bytes));
+    }
+
+    #[test]
+    fn test_digest_writer_is_chunking_independent() {
+        let mut writer = Sha256Writer::new();
+        writer.write_all(b"hello ").unwrap();
+        writer.write_all(b"").unwrap();
+        writer.write_all(b"world").unwrap();
+        assert_eq!(writer.finalize(), Sha256::digest(b"hello world"));
+    }
+}
@@ -0,0 +1,210 @@
+//! Streaming serialization of large sequences, such as the vectors in proving keys.
+//!
+//! Sequences are written as a count followed by each item. The count is a `CompactSize`
+//! by default, and the `_with_length` variants take any `LengthEncoding`.
+
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    error,
+    io::{Read, Result as IoResult, Write},
+    length_encoding::{CompactSize, LengthEncoding},
+    Vec,
+};
+
+use core::marker::PhantomData;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The minimum number of items decoded by each parallel task.
+#[cfg(feature = "parallel")]
+const MIN_ITEMS_PER_TASK: usize = 1 << 10;
+
+/// Writes the number of `items` followed by each item, without collecting them first.
+#[inline]
+pub fn write_iter<T: ToBytes, I, W: Write>(writer: W, items: I) -> IoResult<()>
+where
+    I: IntoIterator<Item = T>,
+    I::IntoIter: ExactSizeIterator,
+{
+    write_iter_with_length::<CompactSize, _, _, _>(writer, items)
+}
+
+/// Writes the number of `items` in the length encoding `L`, followed by each item.
+pub fn write_iter_with_length<L: LengthEncoding, T: ToBytes, I, W: Write>(mut writer: W, items: I) -> IoResult<()>
+where
+    I: IntoIterator<Item = T>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+    let len = items.len();
+    L::write_length(len, &mut writer)?;
+
+    let mut num_written = 0;
+    for item in items {
+        item.write(&mut writer)?;
+        num_written += 1;
+    }
+    match num_written == len {
+        true => Ok(()),
+        false => Err(error(
+            "write_iter: iterator returned a different number of items than its length",
+        )),
+    }
+}
+
+/// An iterator that lazily reads the items of a sequence written by `write_iter`,
+/// or by `write_iter_with_length` with the length encoding `L`.
+pub struct ItemReader<T, R, L: LengthEncoding = CompactSize> {
+    reader: R,
+    remaining: usize,
+    _item: PhantomData<(T, L)>,
+}
+
+impl<T: FromBytes, R: Read> ItemReader<T, R> {
+    /// Reads the number of items from `reader`.
+    #[inline]
+    pub fn new(reader: R) -> IoResult<Self> {
+        Self::new_with_length(reader)
+    }
+}
+
+impl<T: FromBytes, R: Read, L: LengthEncoding> ItemReader<T, R, L> {
+    /// Reads the number of items from `reader`, in the length encoding `L`.
+    pub fn new_with_length(mut reader: R) -> IoResult<Self> {
+        let remaining = L::read_length(&mut reader)?;
+        Ok(Self {
+            reader,
+            remaining,
+            _item: PhantomData,
+        })
+    }
+
+    /// Returns the number of items that remain to be read.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<T: FromBytes, R: Read, L: LengthEncoding> Iterator for ItemReader<T, R, L> {
+    type Item = IoResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let item = T::read(&mut self.reader);
+        // Stop after the first error, as the position in the stream is unknown.
+        if item.is_err() {
+            self.remaining = 0;
+        }
+        Some(item)
+    }
+}
+
+/// Decodes `bytes` as consecutive items of exactly `item_size` bytes each.
+/// With the `parallel` feature, the items are decoded concurrently.
+pub fn read_fixed_items<T: FromBytes + Send>(bytes: &[u8], item_size: usize) -> IoResult<Vec<T>> {
+    if item_size == 0 || !bytes.len().is_multiple_of(item_size) {
+        return Err(error("read_fixed_items: input is not a whole number of items"));
+    }
+
+    #[cfg(feature = "parallel")]
+    let items = bytes
+        .par_chunks_exact(item_size)
+        .with_min_len(MIN_ITEMS_PER_TASK)
+        .map(T::from_bytes_exact)
+        .collect();
+
+    #[cfg(not(feature = "parallel"))]
+    let items = bytes.chunks_exact(item_size).map(T::from_bytes_exact).collect();
+
+    items
+}
+
+/// Decodes a sequence written by `write_iter`, where every item is exactly `item_size` bytes.
+/// The count must account for every remaining byte in `bytes`.
+#[inline]
+pub fn read_fixed_vec<T: FromBytes + Send>(bytes: &[u8], item_size: usize) -> IoResult<Vec<T>> {
+    read_fixed_vec_with_length::<CompactSize, _>(bytes, item_size)
+}
+
+/// Decodes a sequence written by `write_iter_with_length` with the length encoding `L`,
+/// where every item is exactly `item_size` bytes.
+pub fn read_fixed_vec_with_length<L: LengthEncoding, T: FromBytes + Send>(
+    mut bytes: &[u8],
+    item_size: usize,
+) -> IoResult<Vec<T>> {
+    let len = L::read_length(&mut bytes)?;
+    if len.checked_mul(item_size) != Some(bytes.len()) {
+        return Err(error("read_fixed_vec: count does not match the number of bytes"));
+    }
+    read_fixed_items(bytes, item_size)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{length_encoding::Leb128, variable_length_integer::variable_length_integer};
+
+    fn items() -> impl ExactSizeIterator<Item = [u64; 3]> {
+        (0..5000u32).map(u64::from).map(|i| [i, i * i, !i])
+    }
+
+    fn encoded() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_iter(&mut bytes, items()).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_write_iter() {
+        let expected: Vec<[u64; 3]> = items().collect();
+        assert_eq!(
+            encoded(),
+            [variable_length_integer(5000), to_bytes![expected].unwrap()].concat()
+        );
+    }
+
+    #[test]
+    fn test_item_reader() {
+        let bytes = encoded();
+        let mut reader = ItemReader::<[u64; 3], _>::new(&bytes[..]).unwrap();
+        assert_eq!(reader.remaining(), 5000);
+        assert!(reader.by_ref().map(Result::unwrap).eq(items()));
+
+        let truncated = &bytes[..bytes.len() - 1];
+        let results: Vec<_> = ItemReader::<[u64; 3], _>::new(truncated).unwrap().collect();
+        assert_eq!(results.len(), 5000);
+        assert!(results.last().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_read_fixed_vec() {
+        let bytes = encoded();
+        let decoded: Vec<[u64; 3]> = read_fixed_vec(&bytes, 24).unwrap();
+        assert!(decoded.into_iter().eq(items()));
+
+        assert!(read_fixed_vec::<[u64; 3]>(&bytes[..bytes.len() - 24], 24).is_err());
+        assert!(read_fixed_vec::<[u64; 3]>(&bytes, 12).is_err());
+        assert!(read_fixed_items::<[u64; 3]>(&bytes[3..], 12).is_err());
+        assert!(read_fixed_items::<u8>(&bytes, 0).is_err());
+    }
+
+    #[test]
+    fn test_with_length() {
+        let mut bytes = Vec::new();
+        write_iter_with_length::<Leb128, _, _, _>(&mut bytes, items()).unwrap();
+        assert_eq!(&bytes[..2], &[0x88, 0x27]);
+        assert_eq!(&bytes[2..], &encoded()[3..]);
+
+        let reader = ItemReader::<[u64; 3], _, Leb128>::new_with_length(&bytes[..]).unwrap();
+        assert_eq!(reader.remaining(), 5000);
+        assert!(reader.map(Result::unwrap).eq(items()));
+
+        let decoded: Vec<[u64; 3]> = read_fixed_vec_with_length::<Leb128, _>(&bytes, 24).unwrap();
+        assert!(decoded.into_iter().eq(items()));
+        assert!(read_fixed_vec::<[u64; 3]>(&bytes, 24).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Conformance checks for `ToBytes` and `FromBytes` implementations.
+//!
+//! Enabled by the `testing` feature, for use in the tests of downstream crates.
+
+use crate::{
+    bytes::{FromBytes, ToBytes},
+    Vec,
+};
+
+use rand::Rng;
+use std::{fmt::Debug, panic};
+
+/// Asserts that `value` deserializes from its own serialization, consuming
+/// every byte, and that the result serializes back to the same bytes.
+pub fn assert_bytes_roundtrip<T: ToBytes + FromBytes + PartialEq + Debug>(value: &T) {
+    let bytes = to_bytes![value].expect("failed to serialize value");
+
+    let mut reader = &bytes[..];
+    let decoded = T::read(&mut reader).expect("failed to deserialize value");
+    assert_eq!(&decoded, value, "value changed in a round trip");
+    assert!(reader.is_empty(), "{} bytes were not consumed", reader.len());
+
+    let reencoded = to_bytes![decoded].expect("failed to serialize decoded value");
+    assert_eq!(reencoded, bytes, "serialization changed in a round trip");
+}
+
+/// Asserts that every strict prefix of the serialization of `value` fails to deserialize.
+pub fn assert_truncation_rejected<T: ToBytes + FromBytes + Debug>(value: &T) {
+    let bytes = to_bytes![value].expect("failed to serialize value");
+    for length in 0..bytes.len() {
+        if let Ok(decoded) = T::read(&bytes[..length]) {
+            panic!("deserialized {:?} from {} of {} bytes", decoded, length, bytes.len());
+        }
+    }
+}
+
+/// Asserts that deserializing `value` followed by `trailing` returns `value`,
+/// and leaves exactly the `trailing` bytes in the reader.
+pub fn assert_trailing_bytes_untouched<T: ToBytes + FromBytes + PartialEq + Debug>(value: &T, trailing: &[u8]) {
+    let mut bytes = to_bytes![value].expect("failed to serialize value");
+    bytes.extend_from_slice(trailing);
+
+    let mut reader = &bytes[..];
+    let decoded = T::read(&mut reader).expect("failed to deserialize value with trailing bytes");
+    assert_eq!(&decoded, value, "trailing bytes changed the value");
+    assert_eq!(reader, trailing, "trailing bytes were consumed");
+}
+
+/// Asserts that deserializing `iterations` random inputs, each up to `max_length`
+/// bytes long, returns rather than panics.
+pub fn assert_random_input_no_panic<T: FromBytes, R: Rng>(rng: &mut R, iterations: usize, max_length: usize) {
+    for _ in 0..iterations {
+        let length = rng.gen_range(0, max_length + 1);
+        let bytes: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+
+        let result = panic::catch_unwind(|| {
+            let _ = T::read(&bytes[..]);
+        });
+        assert!(result.is_ok(), "deserialization panicked on input {:?}", bytes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{biginteger::BigInteger256, rand::test_rng, BTreeMap, BTreeSet};
+
+    #[test]
+    fn test_primitives_conform() {
+        assert_bytes_roundtrip(&0xabu8);
+        assert_bytes_roundtrip(&-12345i64);
+        assert_bytes_roundtrip(&true);
+        assert_bytes_roundtrip(&[7u32; 5]);
+
+        assert_truncation_rejected(&u64::MAX);
+        assert_truncation_rejected(&[1u16; 3]);
+
+        assert_trailing_bytes_untouched(&0x1234u16, &[0xff; 3]);
+        assert_trailing_bytes_untouched(&(), &[1, 2]);
+
+        let rng = &mut test_rng();
+        assert_random_input_no_panic::<bool, _>(rng, 100, 2);
+        assert_random_input_no_panic::<[u64; 4], _>(rng, 100, 40);
+    }
+
+    #[test]
+    fn test_collections_conform() {
+        let map: BTreeMap<u16, BTreeSet<u8>> = (0..20u16).map(|i| (i * 11, (0..(i as u8)).collect())).collect();
+        assert_bytes_roundtrip(&map);
+        assert_truncation_rejected(&map);
+        assert_trailing_bytes_untouched(&map, &[0, 0, 0]);
+
+        let rng = &mut test_rng();
+        assert_random_input_no_panic::<BTreeMap<u8, BTreeSet<u8>>, _>(rng, 1000, 64);
+    }
+
+    #[test]
+    fn test_biginteger_conforms() {
+        let rng = &mut test_rng();
+        let value: BigInteger256 = rng.gen();
+        assert_bytes_roundtrip(&value);
+        assert_truncation_rejected(&value);
+        assert_trailing_bytes_untouched(&value, &[9]);
+        assert_random_input_no_panic::<BigInteger256, _>(rng, 100, 40);
+    }
+}
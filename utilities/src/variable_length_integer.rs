@@ -1,8 +1,83 @@
 use crate::{
+    bytes::{FromBytes, SerializedSize, ToBytes},
     error,
-    io::{Read, Result as IoResult},
+    io::{Read, Result as IoResult, Write},
+    vec,
+    Vec,
 };
 
+use core::convert::TryFrom;
+
+/// A `u64` serialized as a variable length integer.
+/// https://en.bitcoin.it/wiki/Protocol_documentation#Variable_length_integer
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VarInt(pub u64);
+
+impl VarInt {
+    /// Returns the number of bytes in the encoding of this integer.
+    pub fn encoded_len(&self) -> usize {
+        variable_length_integer_size(self.0)
+    }
+}
+
+impl From<u64> for VarInt {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<VarInt> for u64 {
+    fn from(value: VarInt) -> Self {
+        value.0
+    }
+}
+
+impl ToBytes for VarInt {
+    #[inline]
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        match self.0 {
+            0..=252 => (self.0 as u8).write(&mut writer),
+            253..=65535 => {
+                0xfdu8.write(&mut writer)?;
+                (self.0 as u16).write(&mut writer)
+            }
+            65536..=4_294_967_295 => {
+                0xfeu8.write(&mut writer)?;
+                (self.0 as u32).write(&mut writer)
+            }
+            _ => {
+                0xffu8.write(&mut writer)?;
+                self.0.write(&mut writer)
+            }
+        }
+    }
+}
+
+impl FromBytes for VarInt {
+    #[inline]
+    fn read<R: Read>(mut reader: R) -> IoResult<Self> {
+        let value = match u8::read(&mut reader)? {
+            flag @ 0..=252 => return Ok(Self(flag as u64)),
+            0xfd => (u16::read(&mut reader)? as u64, 253),
+            0xfe => (u32::read(&mut reader)? as u64, 65536),
+            _ => (u64::read(&mut reader)?, 4_294_967_296),
+        };
+
+        // Reject non-canonical encodings, which use more bytes than necessary.
+        match value {
+            (value, minimum) if value >= minimum => Ok(Self(value)),
+            _ => Err(error("Invalid variable size integer")),
+        }
+    }
+}
+
+impl SerializedSize for VarInt {
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.encoded_len()
+    }
+}
+
 /// Returns the variable length integer of the given value.
 /// https://en.bitcoin.it/wiki/Protocol_documentation#Variable_length_integer
 pub fn variable_length_integer(value: u64) -> Vec<u8> {
@@ -18,39 +93,21 @@ pub fn variable_length_integer(value: u64) -> Vec<u8> {
     }
 }
 
+/// Returns the number of bytes in the variable length integer of the given value.
+pub fn variable_length_integer_size(value: u64) -> usize {
+    match value {
+        0..=252 => 1,
+        253..=65535 => 3,
+        65536..=4_294_967_295 => 5,
+        _ => 9,
+    }
+}
+
 /// Decode the value of a variable length integer.
 /// https://en.bitcoin.it/wiki/Protocol_documentation#Variable_length_integer
-pub fn read_variable_length_integer<R: Read>(mut reader: R) -> IoResult<usize> {
-    let mut flag = [0u8; 1];
-    reader.read(&mut flag)?;
-
-    match flag[0] {
-        0..=252 => Ok(flag[0] as usize),
-        0xfd => {
-            let mut size = [0u8; 2];
-            reader.read(&mut size)?;
-            match u16::from_le_bytes(size) {
-                s if s < 253 => Err(error("Invalid variable size integer")),
-                s => Ok(s as usize),
-            }
-        }
-        0xfe => {
-            let mut size = [0u8; 4];
-            reader.read(&mut size)?;
-            match u32::from_le_bytes(size) {
-                s if s < 65536 => Err(error("Invalid variable size integer")),
-                s => Ok(s as usize),
-            }
-        }
-        _ => {
-            let mut size = [0u8; 8];
-            reader.read(&mut size)?;
-            match u64::from_le_bytes(size) {
-                s if s < 4_294_967_296 => Err(error("Invalid variable size integer")),
-                s => Ok(s as usize),
-            }
-        }
-    }
+pub fn read_variable_length_integer<R: Read>(reader: R) -> IoResult<usize> {
+    let VarInt(value) = VarInt::read(reader)?;
+    usize::try_from(value).map_err(|_| error("Variable size integer exceeds usize"))
 }
 
 #[cfg(test)]
@@ -87,6 +144,26 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_variable_length_integer_size() {
+        LENGTH_VALUES.iter().for_each(|(size, _expected_output)| {
+            assert_eq!(
+                variable_length_integer(*size).len(),
+                variable_length_integer_size(*size)
+            );
+        });
+    }
+
+    #[test]
+    fn test_read_truncated_variable_length_integer() {
+        LENGTH_VALUES.iter().for_each(|(size, _expected_output)| {
+            let variable_length_int = variable_length_integer(*size);
+            for length in 0..variable_length_int.len() {
+                assert!(read_variable_length_integer(&variable_length_int[..length]).is_err());
+            }
+        });
+    }
+
     #[test]
     fn test_read_variable_length_integer() {
         LENGTH_VALUES.iter().for_each(|(expected_size, _expected_output)| {
@@ -95,4 +172,22 @@ mod test {
             assert_eq!(*expected_size as usize, size);
         });
     }
+
+    #[test]
+    fn test_var_int() {
+        LENGTH_VALUES.iter().for_each(|(value, expected_output)| {
+            let var_int = VarInt(*value);
+            let bytes = to_bytes![var_int].unwrap();
+            assert_eq!(&expected_output[..var_int.encoded_len()], &bytes[..]);
+            assert_eq!(bytes, variable_length_integer(*value));
+            assert_eq!(VarInt::read(&bytes[..]).unwrap(), var_int);
+        });
+    }
+
+    #[test]
+    fn test_var_int_rejects_non_canonical() {
+        assert!(VarInt::read(&[0xfd, 0xfc, 0x00][..]).is_err());
+        assert!(VarInt::read(&[0xfe, 0xff, 0xff, 0x00, 0x00][..]).is_err());
+        assert!(VarInt::read(&[0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00][..]).is_err());
+    }
 }